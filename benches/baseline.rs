@@ -0,0 +1,111 @@
+//! Times every registered day on its real input and compares the times against the baseline stored
+//! in `benches/baseline.tsv`, failing if any phase got slower than the tolerance allows or has no
+//! time in the baseline yet. Days without a cached input are listed and skipped, as benchmarks never
+//! download anything.
+//!
+//! ```text
+//! cargo bench --bench baseline                      compare against the stored baseline
+//! cargo bench --bench baseline -- --save            store the new times as the baseline
+//! cargo bench --bench baseline -- --tolerance 0.5   allow phases to take 50% longer
+//! ```
+
+use std::process::ExitCode;
+
+use adventofcode2023::advent2023::DAYS;
+use adventofcode2023::input::{cache_dir, InputStore, YEAR};
+use adventofcode2023::timing::{time_day, Baseline};
+
+const BASELINE: &str = "benches/baseline.tsv";
+/// Timings of a few microseconds vary a lot between runs, so only flag clear slowdowns by default.
+const DEFAULT_TOLERANCE: f64 = 0.5;
+
+fn main() -> ExitCode {
+    // cargo passes "--bench" along, so unknown arguments are ignored
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let save = args.iter().any(|a| a == "--save");
+    let tolerance = match args.iter().position(|a| a == "--tolerance") {
+        Some(i) => match args.get(i + 1).and_then(|t| t.parse().ok()) {
+            Some(tolerance) => tolerance,
+            None => {
+                eprintln!("--tolerance needs a number such as 0.25");
+                return ExitCode::from(2);
+            }
+        },
+        None => DEFAULT_TOLERANCE,
+    };
+
+    let store = InputStore::new(YEAR, cache_dir());
+    let stored = match Baseline::load(BASELINE) {
+        Ok(stored) => stored,
+        Err(e) => {
+            eprintln!("{BASELINE}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("{:<14} {:>12} {:>12}", "phase", "current", "baseline");
+    let mut current = Baseline::new();
+    let mut skipped = Vec::new();
+    for day in &DAYS {
+        let input = match store.cached(day.number) {
+            Ok(Some(input)) => input,
+            Ok(None) => {
+                skipped.push(day.number.to_string());
+                continue;
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            }
+        };
+        let times = match time_day(day, &input) {
+            Ok(times) => times,
+            Err(e) => {
+                eprintln!("day {}: {e}", day.number);
+                return ExitCode::FAILURE;
+            }
+        };
+        for (phase, time) in times {
+            let before = stored
+                .get(day.number, phase)
+                .map_or("-".to_string(), |t| format!("{t:?}"));
+            println!(
+                "day {:>2} {phase:<7} {:>12} {before:>12}",
+                day.number,
+                format!("{time:?}")
+            );
+            current.insert(day.number, phase, time);
+        }
+    }
+
+    if !skipped.is_empty() {
+        println!(
+            "skipped days {}: no input in {}",
+            skipped.join(", "),
+            store.cache_dir().display()
+        );
+    }
+
+    if save {
+        if let Err(e) = current.save(BASELINE.as_ref()) {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+        println!("saved {BASELINE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let regressions = stored.regressions(&current, tolerance);
+    for regression in &regressions {
+        println!("slower: {regression}");
+    }
+    let unrecorded = stored.unrecorded(&current);
+    for (day, phase) in &unrecorded {
+        println!("no baseline: day {day} {phase}, record one with --save");
+    }
+    if regressions.is_empty() && unrecorded.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,33 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use adventofcode2023::advent2023::{Part, DAYS};
+use adventofcode2023::input::{cache_dir, InputStore, YEAR};
+
+/// Benchmarks parsing and each part of every registered day on its real input, in a group per day.
+/// Days without a cached input are skipped, as benchmarks never download anything.
+pub fn every_day(c: &mut Criterion) {
+    let store = InputStore::new(YEAR, cache_dir());
+    for day in &DAYS {
+        let Some(input) = store.cached(day.number).expect("readable input cache") else {
+            eprintln!(
+                "skipping day {}, no input in {}",
+                day.number,
+                store.cache_dir().display()
+            );
+            continue;
+        };
+        let parsed = day.parse(&input).expect("input parses");
+
+        let mut group = c.benchmark_group(format!("day{}", day.number));
+        group.bench_function("parse", |b| b.iter(|| day.parse(black_box(&input))));
+        for part in Part::BOTH {
+            group.bench_function(format!("part {part}"), |b| {
+                b.iter(|| parsed.solve(black_box(part)))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, every_day);
+criterion_main!(benches);
@@ -1,16 +1,21 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 
-use adventofcode2023::advent2023::day1;
+use adventofcode2023::advent2023::day1::{self, MissingDigits};
+use adventofcode2023::advent2023::day4::{Card, Cards};
+use adventofcode2023::input::{cache_dir, InputStore, YEAR};
 pub fn day1_part1(c: &mut Criterion) {
     c.bench_function("day1_part1", |b| {
         b.iter(|| {
-            day1::trebuchet_part_1(black_box(
-                "1abc2
+            day1::trebuchet_part_1(
+                black_box(
+                    "1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet"
-                    .to_string(),
-            ))
+                        .to_string(),
+                ),
+                MissingDigits::Error,
+            )
         })
     });
 }
@@ -18,19 +23,77 @@ treb7uchet"
 pub fn day1_part2(c: &mut Criterion) {
     c.bench_function("day1_part2", |b| {
         b.iter(|| {
-            day1::trebuchet_part_2(black_box(
-                "two1nine
+            day1::trebuchet_part_2(
+                black_box(
+                    "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
 7pqrstsixteen"
-                    .to_string(),
-            ))
+                        .to_string(),
+                ),
+                MissingDigits::Error,
+            )
         })
     });
 }
 
-criterion_group!(benches, day1_part1, day1_part2);
+/// Compares the original char based part 1 against the byte level kernel on the real input, and on
+/// the real input repeated to get a sense of how both scale. Skipped when the input is not cached.
+pub fn day1_part1_kernels(c: &mut Criterion) {
+    let store = InputStore::new(YEAR, cache_dir());
+    let Some(input) = store.cached(1).expect("readable input cache") else {
+        eprintln!(
+            "skipping day1_part1_kernels, no input in {}",
+            store.cache_dir().display()
+        );
+        return;
+    };
+    let mut group = c.benchmark_group("day1_part1_kernels");
+    for repeat in [1, 100] {
+        let data = input.repeat(repeat);
+        group.bench_with_input(BenchmarkId::new("chars", repeat), &data, |b, data| {
+            b.iter(|| day1::trebuchet_part_1(black_box(data.clone()), MissingDigits::Error))
+        });
+        group.bench_with_input(BenchmarkId::new("bytes", repeat), &data, |b, data| {
+            b.iter(|| {
+                day1::trebuchet_part_1_bytes(black_box(data.as_bytes()), MissingDigits::Error)
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Counts the copies won on synthetic piles of up to a million cards, which should take time linear
+/// in the number of cards.
+pub fn day4_copies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day4_copies");
+    for size in [1_000, 100_000, 1_000_000] {
+        // every fifth card wins a copy of the next four, which win nothing themselves, so that the
+        // counts stay small however many cards there are
+        let cards = Cards::new(
+            (1..=size)
+                .map(|id| {
+                    let matches = if id % 5 == 0 { 4 } else { 0 };
+                    Card::new(id, (0..matches).collect(), (0..5).collect())
+                })
+                .collect(),
+        );
+        cards.copies().expect("copies fit in a u64");
+        group.bench_with_input(BenchmarkId::from_parameter(size), &cards, |b, cards| {
+            b.iter(|| black_box(cards).copies())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    day1_part1,
+    day1_part2,
+    day1_part1_kernels,
+    day4_copies
+);
 criterion_main!(benches);
@@ -0,0 +1,19 @@
+//! Feeds arbitrary lines to the part 1 line reader, which must never panic and must agree with the
+//! byte level kernel. Run with `cargo +nightly fuzz run day1_numeric_chars`.
+#![no_main]
+
+use adventofcode2023::advent2023::day1::{
+    get_numeric_chars_from_line, trebuchet_part_1_bytes, MissingDigits,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let value = get_numeric_chars_from_line(line.to_string());
+    // the kernel sums lines, so compare only on input that is exactly one line
+    if !line.is_empty() && !line.contains('\n') {
+        assert_eq!(
+            value.map(|v| v as u64),
+            trebuchet_part_1_bytes(line.as_bytes(), MissingDigits::Error).ok()
+        );
+    }
+});
@@ -0,0 +1,12 @@
+//! Feeds arbitrary lines to the part 2 line reader, which must never panic and always give a two
+//! digit value. Run with `cargo +nightly fuzz run day1_part2_line`.
+#![no_main]
+
+use adventofcode2023::advent2023::day1::get_nums_from_line_part2;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Some(value) = get_nums_from_line_part2(line.to_string()) {
+        assert!((0..100).contains(&value));
+    }
+});
@@ -0,0 +1,16 @@
+//! Feeds arbitrary lines to the game reader, which must never panic, and checks that every game it
+//! reads is written back to a line that reads as the same game. Run with
+//! `cargo +nightly fuzz run day2_game_line`.
+#![no_main]
+
+use adventofcode2023::advent2023::day2::read_line_into_game;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Ok(game) = read_line_into_game(1, line) {
+        assert_eq!(
+            Ok(&game),
+            read_line_into_game(1, &game.to_string()).as_ref()
+        );
+    }
+});
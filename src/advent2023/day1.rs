@@ -1,48 +1,103 @@
-/// Representation of each single digit spelled out along with the number it represents.
-const NUMBERS: [(&str, u8); 10] = [
-    ("one", 1),
-    ("two", 2),
-    ("three", 3),
-    ("four", 4),
-    ("five", 5),
-    ("six", 6),
-    ("seven", 7),
-    ("eight", 8),
-    ("nine", 9),
-    ("zero", 0),
-];
-/// Representation of each single digit spelled out in reverse along with the number it represents.
-const REV_NUMBERS: [(&str, u8); 10] = [
-    ("eno", 1),
-    ("owt", 2),
-    ("eerht", 3),
-    ("ruof", 4),
-    ("evif", 5),
-    ("xis", 6),
-    ("neves", 7),
-    ("thgie", 8),
-    ("enin", 9),
-    ("orez", 0),
-];
-
-/// Solution to Day 1 part 1. See test for the expected results
-pub fn trebuchet_part_1(input: String) -> i32 {
-    input
-        .lines()
-        .map(|l| get_numeric_chars_from_line(l.to_string()))
-        .fold(0, |r, l| r + l.expect("a number to be present"))
+pub mod explain;
+pub mod matcher;
+mod scan;
+
+use std::io::BufRead;
+use std::sync::OnceLock;
+
+use super::Solution;
+use crate::error::{Error, Result};
+use matcher::DigitMatcher;
+
+/// Matcher for the digits and English number words used by part 2, built on first use.
+fn english_matcher() -> &'static DigitMatcher {
+    static MATCHER: OnceLock<DigitMatcher> = OnceLock::new();
+    MATCHER.get_or_init(DigitMatcher::english)
+}
+
+/// What to do with a line that has no digit in it, including an empty line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingDigits {
+    /// Leave the line out of the sum.
+    Skip,
+    /// Count the line with a calibration value of 0.
+    Zero,
+    /// Stop with [Error::NoDigitFound], as the puzzle input always has a digit on every line.
+    #[default]
+    Error,
+}
+
+/// Day 1: Trebuchet?!
+pub struct Trebuchet;
+
+impl Solution for Trebuchet {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    type Input = String;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        trebuchet_part_1_bytes(input.as_bytes(), MissingDigits::Error)
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        trebuchet_part_2_streaming(input.as_bytes(), MissingDigits::Error, |_, _| {})
+    }
+}
+
+/// Solution to Day 1 part 1. See test for the expected results. Lines without a digit are handled
+/// as `missing` says, and it fails if the sum does not fit in a u64.
+pub fn trebuchet_part_1(input: String, missing: MissingDigits) -> Result<u64> {
+    sum_calibration_values(
+        &input,
+        |l| get_numeric_chars_from_line(l.to_string()),
+        missing,
+    )
+}
+
+/// Solution to Day 1 part 1 working directly on the bytes of the input. The first and last digit of
+/// each line are found 8 bytes at a time without allocating, making it much faster than
+/// [trebuchet_part_1] on large inputs.
+pub fn trebuchet_part_1_bytes(input: &[u8], missing: MissingDigits) -> Result<u64> {
+    scan::sum_calibration_values(input, missing)
+}
+
+/// Adds up the calibration value found on each line, handling lines without a value as `missing`
+/// says. Errors report the 1-based line number they happened on.
+fn sum_calibration_values(
+    input: &str,
+    value_of: impl Fn(&str) -> Option<i32>,
+    missing: MissingDigits,
+) -> Result<u64> {
+    input.lines().enumerate().try_fold(0u64, |sum, (index, l)| {
+        add_calibration_value(sum, index + 1, value_of(l), missing, &mut |_, _| {})
+    })
 }
 
 /// Obtains the first and last numeric characters in the provided String, combines them and returns
 /// the result. If a number only contains 1 number it will be treated as the first and last number.
-/// If no number is encountered then will return an error
-fn get_numeric_chars_from_line(line: String) -> Result<i32, String> {
+/// If no number is encountered then will return None
+pub fn get_numeric_chars_from_line(line: String) -> Option<i32> {
+    if line.is_empty() {
+        return None;
+    }
+
+    // Indexes count characters rather than bytes so that lines with multi-byte characters are
+    // walked the same way from both ends
+    let chars: Vec<char> = line.chars().collect();
     let mut first_digit_char: Option<char> = None;
     let mut last_digit_char: Option<char> = None;
     let mut front_index: usize = 0;
-    let mut back_index: usize = line.len() - 1;
+    // one past the end until the back is first looked at, so a single character line is not seen
+    // as already traversed before its only character is checked
+    let mut back_index: usize = chars.len();
 
-    for (index, current_char) in line.chars().enumerate() {
+    for (index, current_char) in chars.iter().copied().enumerate() {
         // Traverse over the characters in the line from both front and back at the same time. Once we encounter a
         // number we stop processing from that side. If the indices meet we have traversed the whole line and can
         // proceed with the information we have already.
@@ -67,22 +122,21 @@ fn get_numeric_chars_from_line(line: String) -> Result<i32, String> {
             }
             // Situation 3
             else if first_digit_char.is_none() && last_digit_char.is_none() {
-                return Err("no number found".to_string());
+                return None;
             }
 
             break;
         }
 
-        let chars: Vec<char> = line.chars().collect();
         if first_digit_char.is_none() && current_char.is_ascii_digit() {
             first_digit_char = Some(current_char);
         }
 
         if last_digit_char.is_none() {
             // only decrement if we need to process more
-            back_index = (line.len() - 1) - index;
+            back_index = (chars.len() - 1) - index;
         }
-        let char_from_back = *chars.get(back_index).expect("something");
+        let char_from_back = chars[back_index];
 
         if front_index >= back_index {
             // the indexes have passed each other so one of the situations have happened:
@@ -100,13 +154,13 @@ fn get_numeric_chars_from_line(line: String) -> Result<i32, String> {
             }
             // Situation 3
             else if first_digit_char.is_none() && last_digit_char.is_none() {
-                return Err("No number found".to_string());
+                return None;
             }
 
             break;
         }
 
-        if last_digit_char.is_none() && current_char.is_ascii_digit() {
+        if last_digit_char.is_none() && char_from_back.is_ascii_digit() {
             last_digit_char = Some(char_from_back);
         }
 
@@ -119,85 +173,236 @@ fn get_numeric_chars_from_line(line: String) -> Result<i32, String> {
     let mut num_string = String::from(first_digit_char.expect("a value or 0"));
     num_string.push(last_digit_char.expect("a value or 0"));
 
-    let final_result: i32 = num_string.parse().expect("expected a number");
+    num_string.parse().ok()
+}
+
+/// Solution to Day 1 part 2. See test for the expected results. Lines without a digit are handled
+/// as `missing` says, and it fails if the sum does not fit in a u64.
+pub fn trebuchet_part_2(input: String, missing: MissingDigits) -> Result<u64> {
+    sum_calibration_values(&input, |l| get_nums_from_line_part2(l.to_string()), missing)
+}
+
+/// Same as [trebuchet_part_2] but with the number words of the given matcher, so localized
+/// variants of the puzzle can be solved.
+pub fn trebuchet_part_2_with(
+    input: &str,
+    matcher: &DigitMatcher,
+    missing: MissingDigits,
+) -> Result<u64> {
+    sum_calibration_values(input, |l| matcher.calibration_value(l), missing)
+}
+
+/// Part 1 read line by line from the reader, so inputs of any size are summed in constant memory.
+/// Lines without a digit are handled as `missing` says. The callback is given the 1-based line
+/// number and calibration value of every line that is not skipped.
+pub fn trebuchet_part_1_streaming(
+    reader: impl BufRead,
+    missing: MissingDigits,
+    on_line: impl FnMut(usize, i32),
+) -> Result<u64> {
+    sum_lines_streaming(reader, scan::calibration_value, missing, on_line)
+}
+
+/// Part 2 read line by line from the reader, see [trebuchet_part_1_streaming].
+pub fn trebuchet_part_2_streaming(
+    reader: impl BufRead,
+    missing: MissingDigits,
+    on_line: impl FnMut(usize, i32),
+) -> Result<u64> {
+    sum_calibration_values_streaming(reader, english_matcher(), missing, on_line)
+}
+
+/// Sums the calibration values of every line read from the reader, reusing a single buffer for all
+/// the lines. Line endings are "\n" or "\r\n" like [str::lines], and lines without a digit are
+/// handled as `missing` says. Lines can hold any text, and need not even be valid UTF-8: only the
+/// digits and words of the matcher are looked for, so non-ASCII digits such as full-width numerals
+/// count when the matcher has them, like one built from [matcher::FULL_WIDTH]. The callback is not
+/// called for skipped lines.
+pub fn sum_calibration_values_streaming(
+    reader: impl BufRead,
+    matcher: &DigitMatcher,
+    missing: MissingDigits,
+    on_line: impl FnMut(usize, i32),
+) -> Result<u64> {
+    sum_lines_streaming(reader, |l| matcher.calibration_value(l), missing, on_line)
+}
+
+fn sum_lines_streaming(
+    mut reader: impl BufRead,
+    value_of: impl Fn(&[u8]) -> Option<i32>,
+    missing: MissingDigits,
+    mut on_line: impl FnMut(usize, i32),
+) -> Result<u64> {
+    let mut buffer = Vec::new();
+    let mut sum: u64 = 0;
+    let mut line = 0;
+    loop {
+        buffer.clear();
+        let read = reader
+            .read_until(b'\n', &mut buffer)
+            .map_err(|e| Error::Read {
+                line: line + 1,
+                message: e.to_string(),
+            })?;
+        if read == 0 {
+            return Ok(sum);
+        }
+
+        line += 1;
+        let value = value_of(trim_line_ending(&buffer));
+        sum = add_calibration_value(sum, line, value, missing, &mut on_line)?;
+    }
+}
+
+/// Sums the calibration values of lines that have already been split, such as the lines of a memory
+/// mapped file. See [sum_calibration_values_streaming].
+pub fn sum_calibration_values_of_lines<'a>(
+    lines: impl IntoIterator<Item = &'a [u8]>,
+    matcher: &DigitMatcher,
+    missing: MissingDigits,
+    mut on_line: impl FnMut(usize, i32),
+) -> Result<u64> {
+    lines
+        .into_iter()
+        .enumerate()
+        .try_fold(0, |sum, (index, l)| {
+            let value = matcher.calibration_value(trim_line_ending(l));
+            add_calibration_value(sum, index + 1, value, missing, &mut on_line)
+        })
+}
 
-    Ok(final_result)
+/// Adds the calibration value of a line to the sum, or handles a line without one as `missing` says.
+fn add_calibration_value(
+    sum: u64,
+    line: usize,
+    value: Option<i32>,
+    missing: MissingDigits,
+    on_line: &mut impl FnMut(usize, i32),
+) -> Result<u64> {
+    let value = match (value, missing) {
+        (Some(value), _) => value,
+        (None, MissingDigits::Skip) => return Ok(sum),
+        (None, MissingDigits::Zero) => 0,
+        (None, MissingDigits::Error) => return Err(Error::NoDigitFound { line }),
+    };
+    on_line(line, value);
+    sum.checked_add(value as u64)
+        .ok_or(Error::Overflow { line })
 }
 
-/// Solution to Day 1 part 2. See test for the expected results
-pub fn trebuchet_part_2(input: String) -> i32 {
-    input
-        .lines()
-        .map(|l| get_nums_from_line_part2(l.to_string()))
-        .sum::<i32>()
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
 }
 
 /// Obtains the first and last numeric characters or single digit number spelled out in the provided
 /// String, combines them and returns the result. If a number only contains 1 number it will be
-/// treated as the first and last number. If no number is encountered then will return an error
-fn get_nums_from_line_part2(line: String) -> i32 {
-    let first_digit: i32 = get_first_number_as_u8(line.clone()).expect("a number") as i32;
-    let last_digit: i32 = get_last_number_as_u8(line.clone()).expect("a number") as i32;
-
-    (first_digit * 10) + last_digit
+/// treated as the first and last number. If no number is encountered then will return None
+pub fn get_nums_from_line_part2(line: String) -> Option<i32> {
+    english_matcher().calibration_value(&line)
 }
 
 /// Gets the first number either in the String. The number can be either a numeric character or a
 /// single digit spelled out(i.e. "one", "five", "seven", "zero", etc).
-fn get_first_number_as_u8(string: String) -> Result<u8, String> {
-    get_first_numeric_char_or_number_spelled_lr(string, NUMBERS)
+pub fn get_first_number_as_u8(string: String) -> Option<u8> {
+    english_matcher()
+        .first_and_last(&string)
+        .map(|(first, _)| first.value)
 }
 
 /// Gets the last number either in the String. The number can be either a numeric character or a
 /// single digit spelled out(i.e. "one", "five", "seven", "zero", etc).
-fn get_last_number_as_u8(string: String) -> Result<u8, String> {
-    // flip the string and use the number words reversed to reuse the same logic for going from the front
-    get_first_numeric_char_or_number_spelled_lr(string.chars().rev().collect(), REV_NUMBERS)
+pub fn get_last_number_as_u8(string: String) -> Option<u8> {
+    english_matcher()
+        .first_and_last(&string)
+        .map(|(_, last)| last.value)
 }
 
-/// Obtains the first and last numeric characters or single number spelled out(i.e. "one", "seven", etc.)
-/// in the provided String, combines them and returns the result. If a number only contains 1 number
-/// it will be treated as the first and last number. If no number is encountered then will return an
-/// error
-fn get_first_numeric_char_or_number_spelled_lr(
-    string: String,
-    numbers_tuple: [(&str, u8); 10],
-) -> Result<u8, String> {
-    // based on size of the string, remove words that it cannot be due to being too small
-    let mut possible_numbers: Vec<(&str, u8)> = Vec::new();
-    for tuple_number in numbers_tuple {
-        if tuple_number.0.len() <= string.len() {
-            possible_numbers.push(tuple_number);
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::advent2023::day1::trebuchet_part_1;
+    use crate::test as test_util;
+
+    /// Text without any ASCII digit and digits to insert into it, each with the position in the text
+    /// it goes at, counted in characters.
+    fn text_and_digits() -> impl Strategy<Value = (Vec<char>, Vec<(usize, u8)>)> {
+        (
+            prop::collection::vec(
+                any::<char>().prop_filter("no digits", |c| !c.is_ascii_digit()),
+                0..40,
+            ),
+            prop::collection::vec((any::<prop::sample::Index>(), 0..10u8), 1..6),
+        )
+            .prop_map(|(text, digits)| {
+                let digits = digits
+                    .into_iter()
+                    .map(|(index, digit)| (index.index(text.len() + 1), digit))
+                    .collect();
+                (text, digits)
+            })
+    }
+
+    /// Inserts the digits into the text, later digits going before earlier ones at the same position,
+    /// and returns the line along with the digits in the order they appear.
+    fn insert_digits(text: &[char], digits: &[(usize, u8)]) -> (String, Vec<u8>) {
+        let mut sorted = digits.to_vec();
+        // stable, so digits at the same position keep their order and end up reversed by inserting
+        sorted.sort_by_key(|(position, _)| *position);
+        let mut line: Vec<char> = text.to_vec();
+        let mut in_order = Vec::new();
+        for (position, digit) in sorted.iter().rev() {
+            line.insert(*position, (b'0' + digit) as char);
+            in_order.insert(0, *digit);
         }
+        (line.into_iter().collect(), in_order)
     }
 
-    let string_array: Vec<char> = string.chars().collect();
-    for (index, current_char) in string_array.iter().enumerate() {
-        if (&'0'..=&'9').contains(&current_char) {
-            return Ok(current_char.to_digit(10).unwrap() as u8);
+    proptest! {
+        #[test]
+        fn inserted_digits_are_found((text, digits) in text_and_digits()) {
+            let (line, in_order) = insert_digits(&text, &digits);
+            let expected = in_order[0] as i32 * 10 + *in_order.last().unwrap() as i32;
+            prop_assert_eq!(Some(expected), get_numeric_chars_from_line(line.clone()));
+            prop_assert_eq!(Some(expected), scan::calibration_value(line.as_bytes()));
+            prop_assert_eq!(Some(expected), DigitMatcher::new([]).calibration_value(&line));
         }
 
-        for string_number_association in possible_numbers.iter() {
-            if string[index..].starts_with(string_number_association.0) {
-                return Ok(string_number_association.1);
-            }
+        #[test]
+        fn no_digits_no_value(text in "\\PC*") {
+            let text: String = text.chars().filter(|c| !c.is_ascii_digit()).collect();
+            prop_assert_eq!(None, get_numeric_chars_from_line(text));
         }
-    }
 
-    Err("did not find a number".to_string()) // Make a result and return error here
-}
+        #[test]
+        fn part1_never_panics(line in any::<String>()) {
+            let value = get_numeric_chars_from_line(line.clone());
+            prop_assert_eq!(value, scan::calibration_value(line.as_bytes()));
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::advent2023::day1::trebuchet_part_1;
-    use crate::test as test_util;
+        #[test]
+        fn part2_never_panics(line in any::<String>()) {
+            if let Some(value) = get_nums_from_line_part2(line) {
+                prop_assert!((0..100).contains(&value));
+            }
+        }
+
+        #[test]
+        fn part2_without_letters_is_part1(line in "[^a-zA-Z]*") {
+            prop_assert_eq!(
+                get_numeric_chars_from_line(line.clone()),
+                get_nums_from_line_part2(line)
+            );
+        }
+    }
 
     #[test]
     fn read_line_two_numbers_at_ends() {
         let test_data = "1aabbaa2".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(12, result.unwrap());
     }
 
@@ -205,7 +410,7 @@ mod tests {
     fn read_line_two_numbers_in_middle() {
         let test_data = "abc34def".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(34, result.unwrap())
     }
 
@@ -213,7 +418,7 @@ mod tests {
     fn read_line_two_numbers_in_middleish_off_center() {
         let test_data = "abc34defghijklmnop".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(34, result.unwrap())
     }
 
@@ -221,7 +426,7 @@ mod tests {
     fn read_line_two_numbers_at_start() {
         let test_data = "56abcdefghi".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(56, result.unwrap())
     }
 
@@ -229,7 +434,7 @@ mod tests {
     fn read_line_two_numbers_at_end() {
         let test_data = "abcdefghi78".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(78, result.unwrap())
     }
 
@@ -237,7 +442,7 @@ mod tests {
     fn read_line_one_numbers_at_start() {
         let test_data = "9abcdefg".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(99, result.unwrap())
     }
 
@@ -245,7 +450,7 @@ mod tests {
     fn read_line_one_numbers_towards_start() {
         let test_data = "abc0defghijklmnop".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(00, result.unwrap())
     }
 
@@ -253,7 +458,7 @@ mod tests {
     fn read_line_one_numbers_towards_end() {
         let test_data = "abcdefghijklmnop1qrst".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(11, result.unwrap())
     }
 
@@ -261,7 +466,7 @@ mod tests {
     fn read_line_one_numbers_at_end() {
         let test_data = "abcdefghijklmnop1".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(11, result.unwrap())
     }
 
@@ -269,7 +474,7 @@ mod tests {
     fn read_line_one_numbers_in_middle() {
         let test_data = "abcdef5fedcba".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(55, result.unwrap())
     }
 
@@ -277,7 +482,7 @@ mod tests {
     fn read_line_one_examples_1() {
         let test_data = "1abc2".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(12, result.unwrap())
     }
 
@@ -285,7 +490,7 @@ mod tests {
     fn read_line_one_examples_2() {
         let test_data = "pqr3stu8vwx".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(38, result.unwrap())
     }
 
@@ -293,7 +498,7 @@ mod tests {
     fn read_line_one_examples_3() {
         let test_data = "a1b2c3d4e5f".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(15, result.unwrap())
     }
 
@@ -301,7 +506,7 @@ mod tests {
     fn read_line_one_examples_4() {
         let test_data = "treb7uchet".to_string();
         let result = get_numeric_chars_from_line(test_data);
-        assert_eq!(true, result.is_ok());
+        assert_eq!(true, result.is_some());
         assert_eq!(77, result.unwrap())
     }
 
@@ -313,14 +518,24 @@ a1b2c3d4e5f
 treb7uchet"
             .to_string();
 
-        let result = trebuchet_part_1(test_data);
-        assert_eq!(142, result)
+        let result = trebuchet_part_1(test_data, MissingDigits::Error);
+        assert_eq!(Ok(142), result)
     }
 
     #[test]
-    fn day1_part1_answer() {
-        let result = trebuchet_part_1(test_util::get_day_input(1));
-        assert_eq!(54632, result)
+    fn bytes_matches_owned_input() {
+        let input = test_util::get_day_input(1);
+        assert_eq!(
+            Ok(142),
+            trebuchet_part_1_bytes(
+                b"1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet",
+                MissingDigits::Error
+            )
+        );
+        assert_eq!(
+            trebuchet_part_1(input.clone(), MissingDigits::Error),
+            trebuchet_part_1_bytes(input.as_bytes(), MissingDigits::Error)
+        )
     }
 
     // Part 2
@@ -398,63 +613,318 @@ xtwone3four
 zoneight234
 7pqrstsixteen"
             .to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(281, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(281), result)
     }
 
     #[test]
     fn day1part2_example_1() {
         let test_data = "two1nine".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(29, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(29), result)
     }
 
     #[test]
     fn day1part2_example_2() {
         let test_data = "eightwothree".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(83, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(83), result)
     }
 
     #[test]
     fn day1part2_example_3() {
         let test_data = "abcone2threexyz".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(13, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(13), result)
     }
 
     #[test]
     fn day1part2_example_4() {
         let test_data = "xtwone3four".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(24, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(24), result)
     }
 
     #[test]
     fn day1part2_example_5() {
         let test_data = "4nineeightseven2".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(42, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(42), result)
     }
 
     #[test]
     fn day1part2_example_6() {
         let test_data = "zoneight234".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(14, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(14), result)
     }
 
     #[test]
     fn day1part2_example_7() {
         let test_data = "7pqrstsixteen".to_string();
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(76, result)
+        let result = trebuchet_part_2(test_data, MissingDigits::Error);
+        assert_eq!(Ok(76), result)
+    }
+
+    #[test]
+    fn determine_last_number_overlapping_words() {
+        assert_eq!(Some(2), get_last_number_as_u8("eightwo".to_string()));
+        assert_eq!(Some(8), get_first_number_as_u8("eightwo".to_string()));
+    }
+
+    #[test]
+    fn day1part2_german() {
+        let matcher = DigitMatcher::new(matcher::GERMAN);
+        let result = trebuchet_part_2_with(
+            "zweiundvierzig\nx7achtneunx",
+            &matcher,
+            MissingDigits::Error,
+        );
+        assert_eq!(Ok(24 + 79), result)
+    }
+
+    #[test]
+    fn streaming_matches_owned_input() {
+        let input = test_util::get_day_input(1);
+        let part_1 = trebuchet_part_1_streaming(input.as_bytes(), MissingDigits::Error, |_, _| {});
+        let part_2 = trebuchet_part_2_streaming(input.as_bytes(), MissingDigits::Error, |_, _| {});
+        assert_eq!(
+            trebuchet_part_1(input.clone(), MissingDigits::Error),
+            part_1
+        );
+        assert_eq!(trebuchet_part_2(input, MissingDigits::Error), part_2)
+    }
+
+    #[test]
+    fn streaming_reports_each_line() {
+        let mut values = Vec::new();
+        let result = trebuchet_part_2_streaming(
+            "two1nine\r\neightwothree\n".as_bytes(),
+            MissingDigits::Error,
+            |l, v| values.push((l, v)),
+        );
+        assert_eq!(Ok(29 + 83), result);
+        assert_eq!(vec![(1, 29), (2, 83)], values)
+    }
+
+    #[test]
+    fn streaming_reports_line_without_digit() {
+        let result =
+            trebuchet_part_1_streaming("1abc2\nabc\n".as_bytes(), MissingDigits::Error, |_, _| {});
+        assert_eq!(Err(Error::NoDigitFound { line: 2 }), result)
+    }
+
+    #[test]
+    fn streaming_reports_read_error() {
+        struct Failing;
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+
+        let reader = std::io::BufReader::new(std::io::Read::chain("1abc2\n".as_bytes(), Failing));
+        assert_eq!(
+            Err(Error::Read {
+                line: 2,
+                message: "disk on fire".to_string()
+            }),
+            trebuchet_part_1_streaming(reader, MissingDigits::Error, |_, _| {})
+        )
+    }
+
+    #[test]
+    fn streaming_from_split_lines() {
+        let lines: [&[u8]; 3] = [b"1abc2", b"pqr3stu8vwx", b"treb7uchet"];
+        let result = sum_calibration_values_of_lines(
+            lines,
+            &DigitMatcher::new([]),
+            MissingDigits::Error,
+            |_, _| {},
+        );
+        assert_eq!(Ok(12 + 38 + 77), result)
+    }
+
+    #[test]
+    fn line_without_digit_reports_line_number() {
+        let test_data = "1abc2\nnothing\ntreb7uchet".to_string();
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 2 }),
+            trebuchet_part_1(test_data.clone(), MissingDigits::Error)
+        );
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 2 }),
+            trebuchet_part_2(test_data, MissingDigits::Error)
+        )
+    }
+
+    #[test]
+    fn empty_line_reports_line_number() {
+        let test_data = "1abc2\n\ntreb7uchet".to_string();
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 2 }),
+            trebuchet_part_1(test_data, MissingDigits::Error)
+        )
+    }
+
+    #[test]
+    fn read_line_multi_byte_characters() {
+        assert_eq!(
+            Some(33),
+            get_numeric_chars_from_line("ünï3cödé".to_string())
+        );
+        assert_eq!(Some(12), get_numeric_chars_from_line("é1x2ü".to_string()));
+        assert_eq!(
+            Some(45),
+            get_numeric_chars_from_line("4日本語5".to_string())
+        );
+        assert_eq!(None, get_numeric_chars_from_line("日本語".to_string()));
+    }
+
+    #[test]
+    fn read_line_single_character() {
+        assert_eq!(Some(0), get_numeric_chars_from_line("0".to_string()));
+        assert_eq!(Some(77), get_numeric_chars_from_line("7".to_string()));
+        assert_eq!(None, get_numeric_chars_from_line("x".to_string()));
+    }
+
+    #[test]
+    fn read_line_last_digit_from_back() {
+        // the back of the line must be checked for a digit, not the character the front is at
+        assert_eq!(Some(12), get_numeric_chars_from_line("12ab".to_string()));
+        assert_eq!(Some(12), get_numeric_chars_from_line("ab12".to_string()));
+        assert_eq!(Some(12), get_numeric_chars_from_line("1ab2".to_string()));
+        assert_eq!(Some(39), get_numeric_chars_from_line("3xy9".to_string()));
+    }
+
+    #[test]
+    fn read_line_empty() {
+        assert_eq!(None, get_numeric_chars_from_line(String::new()))
+    }
+
+    #[test]
+    fn crlf_and_unicode_input() {
+        let input = "1abc2\r\nπqr3stu8vwx\r\na1b2c3d4e5f\r\ntreb7üchet\r\n".to_string();
+        assert_eq!(
+            Ok(142),
+            trebuchet_part_1(input.clone(), MissingDigits::Error)
+        );
+        assert_eq!(
+            Ok(142),
+            trebuchet_part_1_bytes(input.as_bytes(), MissingDigits::Error)
+        );
+        assert_eq!(
+            Ok(142),
+            trebuchet_part_1_streaming(input.as_bytes(), MissingDigits::Error, |_, _| {})
+        );
+    }
+
+    #[test]
+    fn lines_without_digits() {
+        let input = "1abc2\r\n\r\nxyz\r\ntreb7uchet\r\n".as_bytes();
+        let matcher = DigitMatcher::new([]);
+        let lenient = |missing| {
+            let mut lines = Vec::new();
+            let sum = sum_calibration_values_streaming(input, &matcher, missing, |l, v| {
+                lines.push((l, v))
+            });
+            (sum, lines)
+        };
+
+        assert_eq!(
+            (Ok(12 + 77), vec![(1, 12), (4, 77)]),
+            lenient(MissingDigits::Skip)
+        );
+        assert_eq!(
+            (Ok(12 + 77), vec![(1, 12), (2, 0), (3, 0), (4, 77)]),
+            lenient(MissingDigits::Zero)
+        );
+        assert_eq!(
+            (Err(Error::NoDigitFound { line: 2 }), vec![(1, 12)]),
+            lenient(MissingDigits::Error)
+        );
+    }
+
+    #[test]
+    fn part_1_lines_without_digits() {
+        let input = "1abc2\r\n\r\nxyz\r\ntreb7uchet\r\n";
+        let modes = [
+            (MissingDigits::Skip, Ok(12 + 77), vec![(1, 12), (4, 77)]),
+            (
+                MissingDigits::Zero,
+                Ok(12 + 77),
+                vec![(1, 12), (2, 0), (3, 0), (4, 77)],
+            ),
+            (
+                MissingDigits::Error,
+                Err(Error::NoDigitFound { line: 2 }),
+                vec![(1, 12)],
+            ),
+        ];
+        for (missing, sum, lines) in modes {
+            assert_eq!(sum, trebuchet_part_1(input.to_string(), missing));
+            assert_eq!(sum, trebuchet_part_1_bytes(input.as_bytes(), missing));
+
+            let mut streamed = Vec::new();
+            let result =
+                trebuchet_part_1_streaming(input.as_bytes(), missing, |l, v| streamed.push((l, v)));
+            assert_eq!((sum.clone(), lines.clone()), (result, streamed));
+
+            let mut split = Vec::new();
+            let matcher = DigitMatcher::new([]);
+            let result = sum_calibration_values_of_lines(
+                input
+                    .as_bytes()
+                    .split(|b| *b == b'\n')
+                    .filter(|l| !l.is_empty()),
+                &matcher,
+                missing,
+                |l, v| split.push((l, v)),
+            );
+            assert_eq!((sum, lines), (result, split));
+        }
+    }
+
+    #[test]
+    fn part_2_lines_without_digits() {
+        let input = "two1nine\r\n\r\nxyz\r\nabcone2threexyz\r\n";
+        let modes = [
+            (MissingDigits::Skip, Ok(29 + 13), vec![(1, 29), (4, 13)]),
+            (
+                MissingDigits::Zero,
+                Ok(29 + 13),
+                vec![(1, 29), (2, 0), (3, 0), (4, 13)],
+            ),
+            (
+                MissingDigits::Error,
+                Err(Error::NoDigitFound { line: 2 }),
+                vec![(1, 29)],
+            ),
+        ];
+        for (missing, sum, lines) in modes {
+            assert_eq!(sum, trebuchet_part_2(input.to_string(), missing));
+            assert_eq!(
+                sum,
+                trebuchet_part_2_with(input, &DigitMatcher::english(), missing)
+            );
+
+            let mut streamed = Vec::new();
+            let result =
+                trebuchet_part_2_streaming(input.as_bytes(), missing, |l, v| streamed.push((l, v)));
+            assert_eq!((sum, lines), (result, streamed));
+        }
     }
 
     #[test]
-    fn day1_part2_answer() {
-        let test_data = test_util::get_day_input(1);
-        let result = trebuchet_part_2(test_data);
-        assert_eq!(54019, result)
+    fn full_width_digits() {
+        let matcher = DigitMatcher::new(matcher::FULL_WIDTH.into_iter().chain(matcher::ENGLISH));
+        let input = "ｔｗｏ１ｎｉｎｅ\nsix２\n\n";
+        let result = sum_calibration_values_streaming(
+            input.as_bytes(),
+            &matcher,
+            MissingDigits::Skip,
+            |_, _| {},
+        );
+        assert_eq!(Ok(11 + 62), result)
     }
 }
@@ -0,0 +1,231 @@
+use std::fmt::{Display, Formatter};
+
+use super::matcher::{DigitMatcher, Match};
+
+/// How the chosen numbers are picked out when a line is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// The first number in green, the last in blue and text that is part of both in cyan.
+    Ansi,
+    /// The first number in square brackets and the last in curly brackets, for output that is not
+    /// a terminal.
+    Brackets,
+}
+
+const GREEN: &str = "\x1b[32m";
+const BLUE: &str = "\x1b[34m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Which numbers of a line were chosen as its first and last digit, and the calibration value they
+/// give. Spans are byte offsets into the text of the line, which has no line ending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The 1-based line number.
+    pub line: usize,
+    pub text: String,
+    /// The first and last number, which are the same when the line only has one. None when the line
+    /// has no number at all.
+    pub matches: Option<(Match, Match)>,
+}
+
+impl Explanation {
+    pub fn first(&self) -> Option<Match> {
+        self.matches.map(|(first, _)| first)
+    }
+
+    pub fn last(&self) -> Option<Match> {
+        self.matches.map(|(_, last)| last)
+    }
+
+    /// The calibration value of the line.
+    pub fn value(&self) -> Option<i32> {
+        self.matches
+            .map(|(first, last)| first.value as i32 * 10 + last.value as i32)
+    }
+
+    /// The text of a match, such as "7" or "seven".
+    pub fn token(&self, m: Match) -> &str {
+        &self.text[m.start..m.end]
+    }
+
+    /// The line with the first and last number highlighted.
+    pub fn render(&self, highlight: Highlight) -> String {
+        let Some((first, last)) = self.matches else {
+            return self.text.clone();
+        };
+
+        // Cut the line wherever a match starts or ends and style each piece on its own, so that
+        // overlapping words such as "eightwo" still show both numbers
+        let mut cuts = vec![
+            0,
+            first.start,
+            first.end,
+            last.start,
+            last.end,
+            self.text.len(),
+        ];
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let mut rendered = String::new();
+        for piece in cuts.windows(2) {
+            let (start, end) = (piece[0], piece[1]);
+            let in_first = first.start <= start && end <= first.end;
+            let in_last = last.start <= start && end <= last.end;
+            let text = &self.text[start..end];
+            match highlight {
+                Highlight::Ansi => {
+                    let colour = match (in_first, in_last) {
+                        (true, true) => Some(CYAN),
+                        (true, false) => Some(GREEN),
+                        (false, true) => Some(BLUE),
+                        (false, false) => None,
+                    };
+                    match colour {
+                        Some(colour) => rendered.push_str(&format!("{colour}{text}{RESET}")),
+                        None => rendered.push_str(text),
+                    }
+                }
+                Highlight::Brackets => {
+                    // opening markers go outside in, closing markers inside out so that a number
+                    // that is both first and last reads "[{7}]"
+                    if start == first.start {
+                        rendered.push('[');
+                    }
+                    if start == last.start {
+                        rendered.push('{');
+                    }
+                    rendered.push_str(text);
+                    if end == last.end {
+                        rendered.push('}');
+                    }
+                    if end == first.end {
+                        rendered.push(']');
+                    }
+                }
+            }
+        }
+        rendered
+    }
+}
+
+/// The line with the numbers in brackets followed by its value, such as "line 2: [eigh{t]wo} = 82".
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rendered = self.render(Highlight::Brackets);
+        match self.value() {
+            Some(value) => write!(f, "line {}: {rendered} = {value}", self.line),
+            None => write!(f, "line {}: {rendered} has no number", self.line),
+        }
+    }
+}
+
+/// Explains how the matcher reads a single line, which should not include its line ending.
+pub fn explain_line(line: usize, text: &str, matcher: &DigitMatcher) -> Explanation {
+    Explanation {
+        line,
+        text: text.to_string(),
+        matches: matcher.first_and_last(text),
+    }
+}
+
+/// Explains every line of the input. Line endings are "\n" or "\r\n" like [str::lines].
+pub fn explain(input: &str, matcher: &DigitMatcher) -> Vec<Explanation> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| explain_line(index + 1, text, matcher))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, explain_line, Highlight};
+    use crate::advent2023::day1::matcher::{DigitMatcher, Match};
+    use crate::advent2023::day1::{get_first_number_as_u8, get_last_number_as_u8};
+
+    #[test]
+    fn spans_and_value() {
+        let e = explain_line(4, "xtwone3four", &DigitMatcher::english());
+        assert_eq!(
+            Some(Match {
+                start: 1,
+                end: 4,
+                value: 2
+            }),
+            e.first()
+        );
+        assert_eq!(
+            Some(Match {
+                start: 7,
+                end: 11,
+                value: 4
+            }),
+            e.last()
+        );
+        assert_eq!("two", e.token(e.first().unwrap()));
+        assert_eq!("four", e.token(e.last().unwrap()));
+        assert_eq!(Some(24), e.value());
+    }
+
+    #[test]
+    fn agrees_with_first_and_last_number() {
+        let matcher = DigitMatcher::english();
+        for line in [
+            "two1nine",
+            "eightwothree",
+            "zoneight234",
+            "7pqrstsixteen",
+            "eightwo",
+        ] {
+            let e = explain_line(1, line, &matcher);
+            assert_eq!(
+                get_first_number_as_u8(line.to_string()),
+                e.first().map(|m| m.value)
+            );
+            assert_eq!(
+                get_last_number_as_u8(line.to_string()),
+                e.last().map(|m| m.value)
+            );
+        }
+    }
+
+    #[test]
+    fn brackets() {
+        let matcher = DigitMatcher::english();
+        let render = |line| explain_line(1, line, &matcher).render(Highlight::Brackets);
+        assert_eq!("x[two]ne3{four}", render("xtwone3four"));
+        assert_eq!("[eigh{t]wo}", render("eightwo"));
+        assert_eq!("treb[{7}]uchet", render("treb7uchet"));
+        assert_eq!("nothing", render("nothing"));
+    }
+
+    #[test]
+    fn ansi() {
+        let matcher = DigitMatcher::english();
+        let render = |line| explain_line(1, line, &matcher).render(Highlight::Ansi);
+        assert_eq!(
+            "\x1b[32meigh\x1b[0m\x1b[36mt\x1b[0m\x1b[34mwo\x1b[0m",
+            render("eightwo")
+        );
+        assert_eq!("a\x1b[36m7\x1b[0mb", render("a7b"));
+    }
+
+    #[test]
+    fn multi_byte_text() {
+        let e = explain_line(1, "ünïcödé7sévén", &DigitMatcher::english());
+        assert_eq!("ünïcödé[{7}]sévén", e.render(Highlight::Brackets));
+        assert_eq!(Some(77), e.value());
+    }
+
+    #[test]
+    fn explain_input() {
+        let explanations = explain("two1nine\r\nabc\r\n", &DigitMatcher::english());
+        let lines: Vec<_> = explanations.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec!["line 1: [two]1{nine} = 29", "line 2: abc has no number"],
+            lines
+        );
+    }
+}
@@ -0,0 +1,293 @@
+/// English number words, the vocabulary of the original puzzle.
+pub const ENGLISH: [(&str, u8); 10] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("zero", 0),
+];
+
+/// German number words.
+pub const GERMAN: [(&str, u8); 10] = [
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+    ("null", 0),
+];
+
+/// Spanish number words.
+pub const SPANISH: [(&str, u8); 10] = [
+    ("uno", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+    ("cero", 0),
+];
+
+/// Full-width digits, as typed with East Asian input methods.
+pub const FULL_WIDTH: [(&str, u8); 10] = [
+    ("０", 0),
+    ("１", 1),
+    ("２", 2),
+    ("３", 3),
+    ("４", 4),
+    ("５", 5),
+    ("６", 6),
+    ("７", 7),
+    ("８", 8),
+    ("９", 9),
+];
+
+/// Arabic-Indic digits.
+pub const ARABIC_INDIC: [(&str, u8); 10] = [
+    ("٠", 0),
+    ("١", 1),
+    ("٢", 2),
+    ("٣", 3),
+    ("٤", 4),
+    ("٥", 5),
+    ("٦", 6),
+    ("٧", 7),
+    ("٨", 8),
+    ("٩", 9),
+];
+
+/// Devanagari digits.
+pub const DEVANAGARI: [(&str, u8); 10] = [
+    ("०", 0),
+    ("१", 1),
+    ("२", 2),
+    ("३", 3),
+    ("४", 4),
+    ("५", 5),
+    ("६", 6),
+    ("७", 7),
+    ("८", 8),
+    ("९", 9),
+];
+
+/// A number found in a line, either a digit or a word from the vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Byte offset the match starts at.
+    pub start: usize,
+    /// Byte offset just past the end of the match.
+    pub end: usize,
+    pub value: u8,
+}
+
+/// Finds the first and last number in a line, where a number is either an ASCII digit or one of the
+/// words of a vocabulary. All words are searched for at once with an Aho-Corasick automaton, so a
+/// line is read a single time no matter how many words there are, and words sharing letters such as
+/// "eightwo" are found from both ends without having to reverse the words.
+pub struct DigitMatcher {
+    /// Next state for every state and input byte, with failure links already followed.
+    transitions: Vec<[u32; 256]>,
+    /// Length and value of every word that ends when the automaton reaches the state.
+    outputs: Vec<Vec<(usize, u8)>>,
+}
+
+impl DigitMatcher {
+    /// Builds a matcher for the ASCII digits along with the given words and the value each one
+    /// stands for.
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u8)>) -> Self {
+        let digits = (0..10u8).map(|d| ([b'0' + d], d));
+        let mut matcher = DigitMatcher {
+            transitions: vec![[0; 256]],
+            outputs: vec![Vec::new()],
+        };
+
+        // Build the trie, where 0 doubles as "no transition yet" since nothing points back to root
+        for (word, value) in words
+            .into_iter()
+            .map(|(w, v)| (w.as_bytes().to_vec(), v))
+            .chain(digits.map(|(d, v)| (d.to_vec(), v)))
+            .filter(|(w, _)| !w.is_empty())
+        {
+            let mut state = 0;
+            for &byte in &word {
+                if matcher.transitions[state][byte as usize] == 0 {
+                    matcher.transitions.push([0; 256]);
+                    matcher.outputs.push(Vec::new());
+                    matcher.transitions[state][byte as usize] =
+                        (matcher.transitions.len() - 1) as u32;
+                }
+                state = matcher.transitions[state][byte as usize] as usize;
+            }
+            matcher.outputs[state].push((word.len(), value));
+        }
+
+        // Breadth first, point missing transitions to where the failure link would go and inherit
+        // the words that end at the failure state, since they are suffixes of this state
+        let mut failure = vec![0usize; matcher.transitions.len()];
+        let mut queue: std::collections::VecDeque<usize> = matcher.transitions[0]
+            .iter()
+            .filter(|&&next| next != 0)
+            .map(|&next| next as usize)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = matcher.outputs[failure[state]].clone();
+            matcher.outputs[state].extend(inherited);
+
+            for byte in 0..256 {
+                let next = matcher.transitions[state][byte] as usize;
+                let fallback = matcher.transitions[failure[state]][byte];
+                if next == 0 {
+                    matcher.transitions[state][byte] = fallback;
+                } else {
+                    failure[next] = fallback as usize;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        matcher
+    }
+
+    /// Matcher for the ASCII digits and the English words used by the puzzle.
+    pub fn english() -> Self {
+        DigitMatcher::new(ENGLISH)
+    }
+
+    /// Every number in the line in the order they end, including ones that overlap. The line can be
+    /// given as a str or as raw bytes.
+    pub fn find_all<'a>(
+        &'a self,
+        line: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> impl Iterator<Item = Match> + 'a {
+        line.as_ref()
+            .iter()
+            .enumerate()
+            .scan(0usize, |state, (index, &byte)| {
+                *state = self.transitions[*state][byte as usize] as usize;
+                Some((index + 1, *state))
+            })
+            .flat_map(move |(end, state)| {
+                self.outputs[state].iter().map(move |&(len, value)| Match {
+                    start: end - len,
+                    end,
+                    value,
+                })
+            })
+    }
+
+    /// The numbers starting first and last in the line, which are the same match when the line only
+    /// has one number.
+    pub fn first_and_last(&self, line: &(impl AsRef<[u8]> + ?Sized)) -> Option<(Match, Match)> {
+        self.find_all(line).fold(None, |found, m| match found {
+            None => Some((m, m)),
+            Some((first, last)) => Some((
+                if m.start < first.start { m } else { first },
+                if m.start > last.start { m } else { last },
+            )),
+        })
+    }
+
+    /// Combines the first and last number in the line into a two digit number.
+    pub fn calibration_value(&self, line: &(impl AsRef<[u8]> + ?Sized)) -> Option<i32> {
+        self.first_and_last(line)
+            .map(|(first, last)| first.value as i32 * 10 + last.value as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigitMatcher, Match, ARABIC_INDIC, ENGLISH, FULL_WIDTH, GERMAN, SPANISH};
+
+    #[test]
+    fn digits_only() {
+        let matcher = DigitMatcher::new([]);
+        assert_eq!(Some(38), matcher.calibration_value("pqr3stu8vwx"));
+        assert_eq!(None, matcher.calibration_value("onetwo"));
+    }
+
+    #[test]
+    fn overlapping_words_from_both_ends() {
+        let matcher = DigitMatcher::english();
+        assert_eq!(Some(82), matcher.calibration_value("eightwo"));
+        assert_eq!(Some(21), matcher.calibration_value("twone"));
+        assert_eq!(Some(18), matcher.calibration_value("oneight"));
+    }
+
+    #[test]
+    fn words_sharing_prefix() {
+        // "seven" and "six" share the first letter, and "sevenine" overlaps with nine
+        let matcher = DigitMatcher::english();
+        assert_eq!(Some(66), matcher.calibration_value("sssix"));
+        assert_eq!(Some(79), matcher.calibration_value("sevenine"));
+    }
+
+    #[test]
+    fn match_spans() {
+        let matcher = DigitMatcher::english();
+        let found: Vec<_> = matcher.find_all("xtwone3").collect();
+        assert_eq!(
+            vec![
+                Match {
+                    start: 1,
+                    end: 4,
+                    value: 2
+                },
+                Match {
+                    start: 3,
+                    end: 6,
+                    value: 1
+                },
+                Match {
+                    start: 6,
+                    end: 7,
+                    value: 3
+                },
+            ],
+            found
+        )
+    }
+
+    #[test]
+    fn german_words() {
+        let matcher = DigitMatcher::new(GERMAN);
+        assert_eq!(Some(53), matcher.calibration_value("xfünfzweidreix"));
+        assert_eq!(Some(71), matcher.calibration_value("siebeneins"));
+    }
+
+    #[test]
+    fn spanish_words() {
+        let matcher = DigitMatcher::new(SPANISH);
+        assert_eq!(Some(16), matcher.calibration_value("unoseis"));
+        assert_eq!(Some(40), matcher.calibration_value("cuatrocero"));
+    }
+
+    #[test]
+    fn custom_vocabulary() {
+        let matcher = DigitMatcher::new([("ten", 1), ("eleven", 2), ("nineteen", 9)]);
+        assert_eq!(Some(19), matcher.calibration_value("tennineteen"));
+        assert_eq!(Some(21), matcher.calibration_value("elevenx5xten"));
+    }
+
+    #[test]
+    fn non_ascii_digits() {
+        let matcher = DigitMatcher::new(FULL_WIDTH);
+        assert_eq!(Some(12), matcher.calibration_value("ａ１ｂ２ｃ"));
+        assert_eq!(Some(37), matcher.calibration_value("３ｘ7"));
+
+        let matcher = DigitMatcher::new(FULL_WIDTH.into_iter().chain(ARABIC_INDIC).chain(ENGLISH));
+        assert_eq!(Some(45), matcher.calibration_value("four٥"));
+        assert_eq!(Some(90), matcher.calibration_value("９zero"));
+    }
+}
@@ -0,0 +1,145 @@
+use super::MissingDigits;
+use crate::error::{Error, Result};
+
+/// Every byte of a word set to 1.
+const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+/// Every byte of a word set to 0x80, the highest bit of each byte.
+const HIGH_BITS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+/// Marks the highest bit of every byte of the word that is an ASCII digit, 8 bytes at a time. Each
+/// byte is compared against '0' - 1 and '9' + 1 using only its lower 7 bits so that nothing carries
+/// over into the next byte, and bytes with the highest bit set are not ASCII so they are dropped.
+fn digit_mask(word: u64) -> u64 {
+    let low_bits = word & (ONES * 0x7f);
+    let below_colon = ONES * (0x7f + b':' as u64) - low_bits;
+    let above_slash = low_bits + ONES * (0x7f - b'/' as u64);
+    below_colon & above_slash & !word & HIGH_BITS
+}
+
+/// Index of the first ASCII digit in the line.
+fn first_digit(line: &[u8]) -> Option<usize> {
+    let chunks = line.chunks_exact(8);
+    let remainder = chunks.remainder();
+    for (index, chunk) in chunks.enumerate() {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().expect("8 bytes")));
+        if mask != 0 {
+            return Some(index * 8 + mask.trailing_zeros() as usize / 8);
+        }
+    }
+
+    let start = line.len() - remainder.len();
+    remainder
+        .iter()
+        .position(u8::is_ascii_digit)
+        .map(|index| start + index)
+}
+
+/// Index of the last ASCII digit in the line.
+fn last_digit(line: &[u8]) -> Option<usize> {
+    let chunks = line.rchunks_exact(8);
+    let remainder = chunks.remainder();
+    for (index, chunk) in chunks.enumerate() {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().expect("8 bytes")));
+        if mask != 0 {
+            let chunk_start = line.len() - (index + 1) * 8;
+            return Some(chunk_start + 7 - mask.leading_zeros() as usize / 8);
+        }
+    }
+
+    remainder.iter().rposition(u8::is_ascii_digit)
+}
+
+/// Combines the first and last ASCII digit of the line into a two digit number.
+pub fn calibration_value(line: &[u8]) -> Option<i32> {
+    let first = line[first_digit(line)?] - b'0';
+    let last = line[last_digit(line)?] - b'0';
+    Some(first as i32 * 10 + last as i32)
+}
+
+/// Sums the part 1 calibration values of every line in the buffer, handling lines without a digit
+/// as `missing` says. Lines are found with memchr and looked at in place, so nothing is allocated
+/// however large the buffer is.
+pub fn sum_calibration_values(input: &[u8], missing: MissingDigits) -> Result<u64> {
+    let mut sum: u64 = 0;
+    let mut start = 0;
+    let mut line = 0;
+    while start < input.len() {
+        let end = memchr::memchr(b'\n', &input[start..]).map_or(input.len(), |i| start + i);
+        line += 1;
+
+        let value = match (calibration_value(&input[start..end]), missing) {
+            (Some(value), _) => value,
+            (None, MissingDigits::Skip | MissingDigits::Zero) => 0,
+            (None, MissingDigits::Error) => return Err(Error::NoDigitFound { line }),
+        };
+        sum = sum
+            .checked_add(value as u64)
+            .ok_or(Error::Overflow { line })?;
+        start = end + 1;
+    }
+
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{calibration_value, digit_mask, first_digit, last_digit, sum_calibration_values};
+    use crate::advent2023::day1::MissingDigits;
+    use crate::error::Error;
+
+    #[test]
+    fn digit_mask_every_byte() {
+        for byte in 0..=255u8 {
+            let mask = digit_mask(u64::from_le_bytes([byte; 8]));
+            let expected = if byte.is_ascii_digit() {
+                0x8080_8080_8080_8080
+            } else {
+                0
+            };
+            assert_eq!(expected, mask, "byte {byte}")
+        }
+    }
+
+    #[test]
+    fn first_and_last_across_chunks() {
+        let line = b"abcdefghij1klmnopqrstu2vwxyzabcdefg";
+        assert_eq!(Some(10), first_digit(line));
+        assert_eq!(Some(22), last_digit(line));
+        assert_eq!(Some(12), calibration_value(line));
+    }
+
+    #[test]
+    fn digits_in_remainders() {
+        // 11 bytes, so the first 3 bytes are left over going backwards and the last 3 going forwards
+        assert_eq!(Some(1), first_digit(b"a5bbbbbbbbb"));
+        assert_eq!(Some(9), last_digit(b"bbbbbbbbb5a"));
+        assert_eq!(Some(1), last_digit(b"a5bbbbbbbbb"));
+        assert_eq!(Some(9), first_digit(b"bbbbbbbbb5a"));
+    }
+
+    #[test]
+    fn no_digits() {
+        assert_eq!(None, calibration_value(b""));
+        assert_eq!(None, calibration_value(b"abcdefghijklmnopq"));
+        assert_eq!(None, calibration_value("ünïcödé".as_bytes()));
+    }
+
+    #[test]
+    fn sum_example() {
+        let input = b"1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        assert_eq!(Ok(142), sum_calibration_values(input, MissingDigits::Error))
+    }
+
+    #[test]
+    fn sum_reports_line() {
+        let input = b"1abc2\npqr3stu8vwx\nabc\ntreb7uchet";
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 3 }),
+            sum_calibration_values(input, MissingDigits::Error)
+        );
+        assert_eq!(
+            Ok(12 + 38 + 77),
+            sum_calibration_values(input, MissingDigits::Skip)
+        )
+    }
+}
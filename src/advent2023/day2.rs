@@ -1,380 +1,557 @@
-use itertools;
+mod bags;
+mod inference;
+mod parser;
+mod report;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+pub use bags::{BestBags, Objective};
+pub use inference::{bags_with_total, Estimate};
+pub use report::{GameReport, Report};
+
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Number of cubes of each color in the bag used for part 1.
+const BAG: [(&str, u8); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+/// Number of cubes of each color, keyed by color name. Colors that are missing have no cubes, so
+/// counts of zero are never stored and two sets with the same cubes always compare equal.
+pub type CubeCounts = BTreeMap<String, u8>;
+
+/// Builds cube counts from (color, count) pairs, dropping colors with no cubes.
+pub fn cube_counts<'a>(counts: impl IntoIterator<Item = (&'a str, u8)>) -> CubeCounts {
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(color, count)| (color.to_string(), count))
+        .collect()
+}
+
+/// Cube counts for the red, green and blue cubes of the original puzzle.
+pub fn rgb(red: u8, green: u8, blue: u8) -> CubeCounts {
+    cube_counts([("red", red), ("green", green), ("blue", blue)])
+}
+
+/// Day 2: Cube Conundrum
+pub struct CubeConundrum;
+
+impl Solution for CubeConundrum {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    type Input = Problem;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_file_into_problem(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.get_solution_part1(&cube_counts(BAG)).into())
+    }
 
-#[derive(PartialEq)]
-struct Problem {
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        input.get_solution_part2()
+    }
+}
+
+/// All the games recorded in the puzzle input. Written out with [Display] it gives back the puzzle
+/// text in its canonical form, one game per line, and it can be read back with [FromStr] or
+/// [read_file_into_problem].
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+pub struct Problem {
     games: Vec<Game>,
 }
 
 impl Problem {
-    fn get_solution_part1(&self, red_max: u8, green_max: u8, blue_max: u8) -> u32 {
+    pub fn new(games: Vec<Game>) -> Self {
+        Problem { games }
+    }
+
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Sum of the ids of the games that are possible with a bag holding the given cubes.
+    pub fn get_solution_part1(&self, limits: &CubeCounts) -> u32 {
         self.games
             .iter()
-            .filter(|g| g.is_valid_for(red_max, green_max, blue_max))
+            .filter(|g| g.is_valid_for(limits))
             .map(|g| g.id)
             .sum()
     }
 
-    fn get_solution_part2(&self) -> u64 {
+    /// Sum of the power of the smallest bag for every game, where the power multiplies the cubes of
+    /// every color seen in the problem. A game that never shows one of those colors has no power.
+    /// Fails when a power or the sum does not fit in a u64, reporting the line of the game in the
+    /// problem as it is written out by [Display].
+    pub fn get_solution_part2(&self) -> Result<u64> {
+        let colors = self.colors();
         self.games
             .iter()
-            .map(|g| g.get_min_cubes_needed())
-            .map(|m| m.0 as u64 * m.1 as u64 * m.2 as u64)
-            .sum()
+            .enumerate()
+            .try_fold(0u64, |sum, (index, g)| {
+                power(&g.get_min_cubes_needed(), &colors)
+                    .and_then(|power| sum.checked_add(power))
+                    .ok_or(Error::Overflow { line: index + 1 })
+            })
+    }
+
+    /// Every color drawn in any of the games. A color only ever drawn with no cubes is not one.
+    pub fn colors(&self) -> BTreeSet<&str> {
+        self.games
+            .iter()
+            .flat_map(|g| g.picks.iter())
+            .flat_map(|p| p.cubes())
+            .filter(|(_, count)| *count > 0)
+            .map(|(color, _)| color)
+            .collect()
+    }
+
+    /// The games as a JSON document of the form
+    /// `{"games":[{"id":1,"picks":[{"blue":3,"red":4}]}]}`, where the colors of every pick keep the
+    /// order they were drawn in.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("games always serialize")
+    }
+
+    /// Reads games back from the JSON written by [Problem::to_json].
+    pub fn from_json(json: &str) -> Result<Problem> {
+        serde_json::from_str(json).map_err(|e| Error::Json {
+            message: e.to_string(),
+        })
     }
 }
 
-#[derive(PartialEq, Debug)]
-struct Game {
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, game) in self.games.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{game}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Problem {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        read_file_into_problem(s)
+    }
+}
+
+/// The cubes of every one of the colors multiplied together, or None when that does not fit in a
+/// u64.
+fn power(cubes: &CubeCounts, colors: &BTreeSet<&str>) -> Option<u64> {
+    colors.iter().try_fold(1u64, |power, c| {
+        power.checked_mul(cubes.get(*c).copied().unwrap_or(0) as u64)
+    })
+}
+
+/// A single game, made of the picks drawn from the bag.
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+pub struct Game {
     id: u32,
     picks: Vec<Pick>,
 }
 
 impl Game {
-    fn is_valid_for(&self, red_max: u8, green_max: u8, blue_max: u8) -> bool {
-        self.picks
-            .iter()
-            .all(|p| p.is_valid_for(red_max, green_max, blue_max))
+    pub fn new(id: u32, picks: Vec<Pick>) -> Self {
+        Game { id, picks }
     }
 
-    fn get_min_cubes_needed(&self) -> (u8, u8, u8) {
-        let max_colors: Vec<_> = self
-            .picks
-            .iter()
-            .map(|p| (p.red, p.green, p.blue))
-            .collect();
-        let (r, g, b): (Vec<_>, Vec<_>, Vec<_>) = itertools::multiunzip(max_colors);
-        (
-            *r.iter().max().expect("a red max"),
-            *g.iter().max().expect("a green max"),
-            *b.iter().max().expect("a blue max"),
-        )
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn picks(&self) -> &[Pick] {
+        &self.picks
+    }
+
+    fn is_valid_for(&self, limits: &CubeCounts) -> bool {
+        self.picks.iter().all(|p| p.is_valid_for(limits))
+    }
+
+    /// The fewest cubes of each color the bag could have held for this game to be possible.
+    fn get_min_cubes_needed(&self) -> CubeCounts {
+        let mut min = CubeCounts::new();
+        let cubes = self.picks.iter().flat_map(|p| p.cubes());
+        for (color, count) in cubes.filter(|(_, count)| *count > 0) {
+            let current = min.entry(color.to_string()).or_default();
+            *current = (*current).max(count);
+        }
+        min
+    }
+}
+
+/// Written as a line of the puzzle input, such as "Game 1: 3 blue, 4 red; 1 red, 2 green".
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (index, pick) in self.picks.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{pick}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Game {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        read_line_into_game(1, s)
     }
 }
 
-#[derive(PartialEq, Debug)]
-struct Pick {
-    red: u8,
-    green: u8,
-    blue: u8,
+/// The number of cubes of each color drawn from the bag at once. The colors keep the order they were
+/// drawn in, along with any color written with no cubes, so that the pick is written back the way it
+/// was read. Two picks with the same colors and counts are equal whatever their order.
+#[derive(Debug)]
+pub struct Pick {
+    cubes: Vec<(String, u8)>,
 }
 
 impl Pick {
-    fn is_valid_for(&self, red_max: u8, green_max: u8, blue_max: u8) -> bool {
-        if red_max < self.red {
-            return false;
-        }
-        if green_max < self.green {
-            return false;
+    pub fn new(cubes: CubeCounts) -> Self {
+        Pick {
+            cubes: cubes.into_iter().collect(),
         }
-        if blue_max < self.blue {
-            return false;
+    }
+
+    /// A pick that keeps the colors in the order given. Counts for a color that is repeated are
+    /// added together. Returns None if a color ends up with more cubes than fit in a u8.
+    pub fn in_order(cubes: impl IntoIterator<Item = (String, u8)>) -> Option<Self> {
+        let mut merged: Vec<(String, u8)> = Vec::new();
+        for (color, count) in cubes {
+            match merged.iter_mut().find(|(c, _)| *c == color) {
+                Some((_, total)) => *total = total.checked_add(count)?,
+                None => merged.push((color, count)),
+            }
         }
+        Some(Pick { cubes: merged })
+    }
+
+    /// A pick of red, green and blue cubes like in the original puzzle.
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Pick::new(rgb(red, green, blue))
+    }
+
+    /// Number of cubes of the color in the pick.
+    pub fn count(&self, color: &str) -> u8 {
+        self.cubes
+            .iter()
+            .find(|(c, _)| c == color)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// The colors in the pick and the number of cubes of each, in the order they were drawn.
+    pub fn cubes(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.cubes
+            .iter()
+            .map(|(color, count)| (color.as_str(), *count))
+    }
 
-        true
+    /// Whether the bag could have given this pick. Colors missing from the limits are not in the
+    /// bag at all.
+    fn is_valid_for(&self, limits: &CubeCounts) -> bool {
+        self.cubes()
+            .all(|(color, count)| count <= limits.get(color).copied().unwrap_or(0))
     }
 }
 
-fn read_file_into_problem(full_data: &str) -> Problem {
-    let games: Vec<_> = full_data.lines().map(|s| read_line_into_game(s)).collect();
+impl PartialEq for Pick {
+    fn eq(&self, other: &Self) -> bool {
+        // colors are never repeated, so the same length and counts means the same cubes
+        self.cubes.len() == other.cubes.len()
+            && self
+                .cubes()
+                .all(|(color, count)| other.count(color) == count)
+    }
+}
+
+/// Written as part of a game line, such as "3 blue, 4 red" or "0 red". A pick without any color is
+/// written as nothing at all, which can not be read back.
+impl Display for Pick {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, (color, count)) in self.cubes().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{count} {color}")?;
+        }
+        Ok(())
+    }
+}
 
-    Problem { games }
+/// A JSON object from color to count, written in the order the colors were drawn.
+impl Serialize for Pick {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.cubes.len()))?;
+        for (color, count) in self.cubes() {
+            map.serialize_entry(color, &count)?;
+        }
+        map.end()
+    }
 }
 
-fn read_line_into_game(line: &str) -> Game {
-    let game_id_and_data: Vec<_> = line.split(':').collect();
-    let game_id = game_id_and_data[0];
-    let game_data = game_id_and_data[1];
-
-    let game_id = game_id.split_ascii_whitespace().collect::<Vec<_>>()[1];
-    let picks: Vec<_> = game_data.split(';').collect();
-
-    let mut parsed_picks: Vec<Pick> = Vec::new();
-
-    for pick in picks {
-        let mut red_count: u8 = 0;
-        let mut green_count: u8 = 0;
-        let mut blue_count: u8 = 0;
-        let cubes: Vec<_> = pick.split(',').collect();
-        for cube in cubes {
-            let cr = cube.split_ascii_whitespace().collect::<Vec<_>>();
-            let number = cr[0].parse::<u8>().expect("a valid number");
-            match cr[1] {
-                "red" => {
-                    red_count += number;
-                }
-                "green" => {
-                    green_count += number;
-                }
-                "blue" => {
-                    blue_count += number;
-                }
-                &_ => {
-                    panic!("unknown color");
+impl<'de> Deserialize<'de> for Pick {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct PickVisitor;
+
+        impl<'de> Visitor<'de> for PickVisitor {
+            type Value = Pick;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a map from color to number of cubes")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Pick, A::Error> {
+                let mut cubes = Vec::new();
+                while let Some(entry) = map.next_entry::<String, u8>()? {
+                    cubes.push(entry);
                 }
+                Pick::in_order(cubes).ok_or(de::Error::custom("too many cubes of one color"))
             }
         }
-        parsed_picks.push(Pick {
-            red: red_count,
-            green: green_count,
-            blue: blue_count,
-        })
+
+        deserializer.deserialize_map(PickVisitor)
     }
+}
 
-    Game {
-        id: game_id.parse::<u32>().expect("u32 id"),
-        picks: parsed_picks,
+/// Reads every line of the input into a game, stopping at the first line that is not a valid game.
+/// Blank lines are ignored.
+pub fn read_file_into_problem(full_data: &str) -> Result<Problem> {
+    let games = game_lines(full_data)
+        .map(|(line_number, line)| read_line_into_game(line_number, line))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Problem { games })
+}
+
+/// Reads every line of the input into a game like [read_file_into_problem], but carries on past
+/// invalid lines. The games that could be read are returned along with an error for every line that
+/// could not.
+pub fn read_file_into_problem_recovering(full_data: &str) -> (Problem, Vec<Error>) {
+    let mut games = Vec::new();
+    let mut errors = Vec::new();
+    for (line_number, line) in game_lines(full_data) {
+        match read_line_into_game(line_number, line) {
+            Ok(game) => games.push(game),
+            Err(e) => errors.push(e),
+        }
     }
+
+    (Problem { games }, errors)
+}
+
+/// The non blank lines of the input along with their 1-based line numbers.
+fn game_lines(full_data: &str) -> impl Iterator<Item = (usize, &str)> {
+    full_data
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Reads a single line such as "Game 1: 3 blue, 4 red; 1 red, 2 green" into a game. The line number
+/// is only used to describe where an error happened.
+pub fn read_line_into_game(line_number: usize, line: &str) -> Result<Game> {
+    parser::parse_game(line_number, line)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{read_file_into_problem, read_line_into_game, Game, Pick, Problem};
+    use super::{
+        cube_counts, read_file_into_problem, read_file_into_problem_recovering,
+        read_line_into_game, rgb, Game, Pick, Problem,
+    };
+    use proptest::prelude::*;
+
+    use crate::error::Error;
     use crate::test::get_day_input;
 
+    /// Picks with up to four different colors of zero to 255 cubes, in a random order.
+    fn pick() -> impl Strategy<Value = Pick> {
+        prop::collection::btree_map("[a-zA-Zéüß]{1,8}", 0..=255u8, 1..=4)
+            .prop_map(|cubes| cubes.into_iter().collect::<Vec<_>>())
+            .prop_shuffle()
+            .prop_map(|cubes| Pick::in_order(cubes).unwrap())
+    }
+
+    fn game() -> impl Strategy<Value = Game> {
+        (any::<u32>(), prop::collection::vec(pick(), 1..6))
+            .prop_map(|(id, picks)| Game { id, picks })
+    }
+
+    proptest! {
+        #[test]
+        fn written_game_reads_back(game in game()) {
+            let line = game.to_string();
+            let read = read_line_into_game(1, &line).unwrap();
+            prop_assert_eq!(&line, &read.to_string());
+            prop_assert_eq!(game, read);
+        }
+
+        #[test]
+        fn json_reads_back(games in prop::collection::vec(game(), 0..5)) {
+            let p = Problem { games };
+            prop_assert_eq!(&p, &Problem::from_json(&p.to_json()).unwrap());
+        }
+
+        #[test]
+        fn read_line_never_panics(line in any::<String>()) {
+            let _ = read_line_into_game(1, &line);
+        }
+
+        #[test]
+        fn read_line_from_game_tokens_never_panics(
+            tokens in prop::collection::vec(
+                prop::sample::select(vec!["Game", " ", "\t", "1", "255", "256", ":", ";", ",", "red", "grün", "\r", "x"]),
+                0..20
+            )
+        ) {
+            let _ = read_line_into_game(1, &tokens.concat());
+        }
+
+        #[test]
+        fn read_line_writes_back(
+            line in "Game [0-9]{1,3}: [0-9]{1,2} [a-c]( ?, [0-9]{1,2} [a-c]){0,2}(; [0-9]{1,2} [a-c]){0,2}"
+        ) {
+            // what the fuzz target checks, on lines that are always valid games
+            let game = read_line_into_game(1, &line).unwrap();
+            let written = game.to_string();
+            prop_assert_eq!(Ok(game), read_line_into_game(1, &written));
+        }
+    }
+
     #[test]
     fn pick_validation_max_reached() {
-        let p = Pick {
-            red: 1,
-            green: 1,
-            blue: 1,
-        };
+        let p = Pick::rgb(1, 1, 1);
 
-        assert_eq!(true, p.is_valid_for(1, 1, 1))
+        assert_eq!(true, p.is_valid_for(&rgb(1, 1, 1)))
     }
 
     #[test]
     fn pick_validation_zeros() {
-        let p = Pick {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
+        let p = Pick::rgb(0, 0, 0);
 
-        assert_eq!(true, p.is_valid_for(0, 0, 0))
+        assert_eq!(true, p.is_valid_for(&rgb(0, 0, 0)))
     }
 
     #[test]
     fn pick_validation_simple() {
-        let p = Pick {
-            red: 50,
-            green: 20,
-            blue: 1,
-        };
+        let p = Pick::rgb(50, 20, 1);
 
-        assert_eq!(false, p.is_valid_for(49, 21, 1))
+        assert_eq!(false, p.is_valid_for(&rgb(49, 21, 1)))
     }
 
     #[test]
     fn game_validation_simple() {
-        let picks = vec![Pick {
-            red: 49,
-            green: 20,
-            blue: 1,
-        }];
+        let picks = vec![Pick::rgb(49, 20, 1)];
 
         let g = Game { id: 0, picks };
 
-        assert_eq!(true, g.is_valid_for(50, 20, 1))
+        assert_eq!(true, g.is_valid_for(&rgb(50, 20, 1)))
     }
 
     #[test]
     fn game_validation_simple_false() {
-        let picks = vec![Pick {
-            red: 50,
-            green: 20,
-            blue: 1,
-        }];
+        let picks = vec![Pick::rgb(50, 20, 1)];
 
         let g = Game { id: 0, picks };
 
-        assert_eq!(false, g.is_valid_for(49, 21, 1))
+        assert_eq!(false, g.is_valid_for(&rgb(49, 21, 1)))
     }
 
     #[test]
     fn game_get_min_simple() {
         let game = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 5,
-                    green: 0,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 5,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 0,
-                    blue: 5,
-                },
-            ],
+            picks: vec![Pick::rgb(5, 0, 0), Pick::rgb(0, 5, 0), Pick::rgb(0, 0, 5)],
         };
 
-        assert_eq!(game.get_min_cubes_needed(), (5, 5, 5))
+        assert_eq!(game.get_min_cubes_needed(), rgb(5, 5, 5))
     }
 
     #[test]
     fn game_get_min_no_zero() {
         let game = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 5,
-                    green: 1,
-                    blue: 3,
-                },
-                Pick {
-                    red: 4,
-                    green: 5,
-                    blue: 4,
-                },
-                Pick {
-                    red: 4,
-                    green: 4,
-                    blue: 5,
-                },
-            ],
+            picks: vec![Pick::rgb(5, 1, 3), Pick::rgb(4, 5, 4), Pick::rgb(4, 4, 5)],
         };
 
-        assert_eq!(game.get_min_cubes_needed(), (5, 5, 5))
+        assert_eq!(game.get_min_cubes_needed(), rgb(5, 5, 5))
     }
 
     #[test]
     fn solution_part2_one_game() {
         let game = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 5,
-                    green: 0,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 5,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 0,
-                    blue: 5,
-                },
-            ],
+            picks: vec![Pick::rgb(5, 0, 0), Pick::rgb(0, 5, 0), Pick::rgb(0, 0, 5)],
         };
 
         let p = Problem { games: vec![game] };
-        assert_eq!(125, p.get_solution_part2())
+        assert_eq!(Ok(125), p.get_solution_part2())
     }
     #[test]
     fn solution_part2_multiple_games() {
         let game1 = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 5,
-                    green: 0,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 5,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 0,
-                    blue: 5,
-                },
-            ],
+            picks: vec![Pick::rgb(5, 0, 0), Pick::rgb(0, 5, 0), Pick::rgb(0, 0, 5)],
         };
 
         let game2 = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 5,
-                    green: 0,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 5,
-                    blue: 0,
-                },
-                Pick {
-                    red: 0,
-                    green: 0,
-                    blue: 5,
-                },
-            ],
+            picks: vec![Pick::rgb(5, 0, 0), Pick::rgb(0, 5, 0), Pick::rgb(0, 0, 5)],
         };
 
         let p = Problem {
             games: vec![game1, game2],
         };
-        assert_eq!(250, p.get_solution_part2())
+        assert_eq!(Ok(250), p.get_solution_part2())
     }
     #[test]
     fn game_validation_mulitple_picks_true() {
         let picks = vec![
-            Pick {
-                red: 50,
-                green: 20,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
+            Pick::rgb(50, 20, 1),
+            Pick::rgb(1, 1, 1),
+            Pick::rgb(1, 1, 1),
+            Pick::rgb(1, 1, 1),
         ];
 
         let g = Game { id: 0, picks };
 
-        assert_eq!(true, g.is_valid_for(50, 20, 1))
+        assert_eq!(true, g.is_valid_for(&rgb(50, 20, 1)))
     }
 
     #[test]
     fn game_validation_mulitple_picks_false() {
         let picks = vec![
-            Pick {
-                red: 50,
-                green: 20,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
-            Pick {
-                red: 1,
-                green: 1,
-                blue: 1,
-            },
+            Pick::rgb(50, 20, 1),
+            Pick::rgb(1, 1, 1),
+            Pick::rgb(1, 1, 1),
+            Pick::rgb(1, 1, 1),
         ];
 
         let g = Game { id: 0, picks };
 
-        assert_eq!(false, g.is_valid_for(1, 2, 1))
+        assert_eq!(false, g.is_valid_for(&rgb(1, 2, 1)))
     }
 
     #[test]
@@ -383,31 +560,15 @@ mod tests {
             games: vec![Game {
                 id: 20,
                 picks: vec![
-                    Pick {
-                        red: 50,
-                        green: 20,
-                        blue: 1,
-                    },
-                    Pick {
-                        red: 1,
-                        green: 1,
-                        blue: 1,
-                    },
-                    Pick {
-                        red: 1,
-                        green: 1,
-                        blue: 1,
-                    },
-                    Pick {
-                        red: 1,
-                        green: 1,
-                        blue: 1,
-                    },
+                    Pick::rgb(50, 20, 1),
+                    Pick::rgb(1, 1, 1),
+                    Pick::rgb(1, 1, 1),
+                    Pick::rgb(1, 1, 1),
                 ],
             }],
         };
 
-        assert_eq!(0, p.get_solution_part1(1, 1, 1))
+        assert_eq!(0, p.get_solution_part1(&rgb(1, 1, 1)))
     }
 
     #[test]
@@ -417,57 +578,25 @@ mod tests {
                 Game {
                     id: 20,
                     picks: vec![
-                        Pick {
-                            red: 50,
-                            green: 20,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
+                        Pick::rgb(50, 20, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
                     ],
                 },
                 Game {
                     id: 30,
                     picks: vec![
-                        Pick {
-                            red: 50,
-                            green: 20,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
+                        Pick::rgb(50, 20, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
                     ],
                 },
             ],
         };
 
-        assert_eq!(0, p.get_solution_part1(1, 1, 1))
+        assert_eq!(0, p.get_solution_part1(&rgb(1, 1, 1)))
     }
 
     #[test]
@@ -477,107 +606,43 @@ mod tests {
                 Game {
                     id: 20,
                     picks: vec![
-                        Pick {
-                            red: 50,
-                            green: 20,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
+                        Pick::rgb(50, 20, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
                     ],
                 },
                 Game {
                     id: 30,
                     picks: vec![
-                        Pick {
-                            red: 50,
-                            green: 20,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
+                        Pick::rgb(50, 20, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
                     ],
                 },
                 Game {
                     id: 7,
                     picks: vec![
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
-                        Pick {
-                            red: 1,
-                            green: 1,
-                            blue: 1,
-                        },
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
+                        Pick::rgb(1, 1, 1),
                     ],
                 },
             ],
         };
 
-        assert_eq!(7, p.get_solution_part1(1, 1, 1))
+        assert_eq!(7, p.get_solution_part1(&rgb(1, 1, 1)))
     }
 
     #[test]
     fn read_line_into_game_test() {
         let data = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let result = read_line_into_game(data);
+        let result = read_line_into_game(1, data).unwrap();
         let expected = Game {
             id: 1,
-            picks: vec![
-                Pick {
-                    red: 4,
-                    green: 0,
-                    blue: 3,
-                },
-                Pick {
-                    red: 1,
-                    green: 2,
-                    blue: 6,
-                },
-                Pick {
-                    red: 0,
-                    green: 2,
-                    blue: 0,
-                },
-            ],
+            picks: vec![Pick::rgb(4, 0, 3), Pick::rgb(1, 2, 6), Pick::rgb(0, 2, 0)],
         };
 
         assert_eq!(result, expected)
@@ -592,22 +657,254 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
             .to_string();
 
-        let p = read_file_into_problem(&data);
-        assert_eq!(8, p.get_solution_part1(12, 13, 14))
+        let p = read_file_into_problem(&data).unwrap();
+        assert_eq!(8, p.get_solution_part1(&rgb(12, 13, 14)))
+    }
+
+    #[test]
+    fn read_line_extra_colors() {
+        let data = "Game 1: 3 blue, 4 yellow; 2 yellow, 1 yellow";
+        let expected = Game {
+            id: 1,
+            picks: vec![
+                Pick::new(cube_counts([("blue", 3), ("yellow", 4)])),
+                Pick::new(cube_counts([("yellow", 3)])),
+            ],
+        };
+        assert_eq!(Ok(expected), read_line_into_game(1, data))
+    }
+
+    #[test]
+    fn read_line_bad_color() {
+        let data = "Game 1: 3 blue, 4 r3d";
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 19,
+                token: "r3d".to_string(),
+                expected: "a color",
+                excerpt: data.to_string(),
+            }),
+            read_line_into_game(1, data)
+        )
+    }
+
+    #[test]
+    fn pick_validation_missing_color_in_limits() {
+        let p = Pick::new(cube_counts([("red", 1), ("yellow", 1)]));
+        assert_eq!(false, p.is_valid_for(&rgb(5, 5, 5)));
+        assert_eq!(
+            true,
+            p.is_valid_for(&cube_counts([("red", 1), ("yellow", 1)]))
+        )
+    }
+
+    #[test]
+    fn game_get_min_extra_colors() {
+        let game = read_line_into_game(1, "Game 1: 3 yellow, 1 red; 5 yellow; 2 purple").unwrap();
+        assert_eq!(
+            cube_counts([("yellow", 5), ("red", 1), ("purple", 2)]),
+            game.get_min_cubes_needed()
+        )
+    }
+
+    #[test]
+    fn solution_extra_colors() {
+        let data = "Game 1: 3 yellow, 1 red, 2 green, 2 blue
+Game 2: 1 yellow, 1 red, 1 green, 1 blue; 9 yellow
+Game 3: 1 red, 1 green, 1 blue";
+        let p = read_file_into_problem(data).unwrap();
+        let limits = cube_counts([("red", 5), ("green", 5), ("blue", 5), ("yellow", 5)]);
+        assert_eq!(4, p.get_solution_part1(&limits));
+        // game 3 never shows yellow so it has no power
+        assert_eq!(Ok(12 + 9), p.get_solution_part2())
+    }
+
+    #[test]
+    fn power_overflow() {
+        // 255⁸ still fits in a u64 but 255⁹ does not
+        let colors = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+        let pick = |n: usize| Pick::new(cube_counts(colors[..n].iter().map(|c| (*c, 255))));
+        let eight = Problem::new(vec![Game::new(1, vec![pick(8)])]);
+        assert_eq!(Ok(255u64.pow(8)), eight.get_solution_part2());
+
+        let nine = Problem::new(vec![
+            Game::new(1, vec![pick(1)]),
+            Game::new(2, vec![pick(9)]),
+        ]);
+        assert_eq!(Err(Error::Overflow { line: 2 }), nine.get_solution_part2());
+        assert_eq!(
+            Err(Error::Overflow { line: 2 }),
+            read_file_into_problem(&nine.to_string())
+                .unwrap()
+                .get_solution_part2()
+        );
+    }
+
+    #[test]
+    fn read_line_missing_colon() {
+        let data = "Game 1 3 blue";
+        assert_eq!(
+            Err(Error::Parse {
+                line: 4,
+                column: 8,
+                token: "3".to_string(),
+                expected: "':' after the game id",
+                excerpt: data.to_string(),
+            }),
+            read_line_into_game(4, data)
+        )
+    }
+
+    #[test]
+    fn read_line_bad_number() {
+        let data = "Game 1: 3 blue, x red";
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 17,
+                token: "x".to_string(),
+                expected: "a cube count",
+                excerpt: data.to_string(),
+            }),
+            read_line_into_game(1, data)
+        )
+    }
+
+    #[test]
+    fn read_line_count_overflow() {
+        let data = "Game 1: 300 blue";
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 9,
+                token: "300".to_string(),
+                expected: "a count of at most 255 cubes",
+                excerpt: data.to_string(),
+            }),
+            read_line_into_game(1, data)
+        )
+    }
+
+    #[test]
+    fn read_file_reports_line_number() {
+        let data = "Game 1: 3 blue\nGame two: 1 red";
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 6,
+                token: "two".to_string(),
+                expected: "a game id",
+                excerpt: "Game two: 1 red".to_string(),
+            }),
+            read_file_into_problem(data).map(|_| ())
+        )
     }
 
     #[test]
-    fn day2_part_1_answer() {
+    fn read_file_recovering_keeps_valid_games() {
+        let data = "Game 1: 3 blue\r\nGame 2: 3red\r\n\r\nGame 3: 1 red;\r\nGame 4: 2 green\r\n";
+        let (p, errors) = read_file_into_problem_recovering(data);
+
+        let ids: Vec<_> = p.games.iter().map(|g| g.id).collect();
+        assert_eq!(vec![1, 4], ids);
+        let lines: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                Error::Parse { line, .. } => *line,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(vec![2, 4], lines)
+    }
+
+    #[test]
+    fn game_display_keeps_pick_order() {
+        let data = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let game: Game = data.parse().unwrap();
+        assert_eq!(data, game.to_string())
+    }
+
+    #[test]
+    fn game_display_canonical() {
+        let game: Game = "  Game 7 :3 blue,4 red ;2 yellow, 1 yellow"
+            .parse()
+            .unwrap();
+        assert_eq!("Game 7: 3 blue, 4 red; 3 yellow", game.to_string())
+    }
+
+    #[test]
+    fn zero_counts_round_trip() {
+        for data in [
+            "Game 1: 0 red",
+            "Game 1: 3 red, 0 blue",
+            "Game 2: 0 red; 1 red, 0 red",
+        ] {
+            let p: Problem = data.parse().unwrap();
+            assert_eq!(data.replace("1 red, 0 red", "1 red"), p.to_string());
+            assert_eq!(p, p.to_string().parse().unwrap());
+            assert_eq!(p, Problem::from_json(&p.to_json()).unwrap());
+        }
+
+        // a color drawn with no cubes is not needed in the bag
+        let p: Problem = "Game 1: 0 red, 2 blue\nGame 2: 3 blue".parse().unwrap();
+        assert_eq!(Ok(2 + 3), p.get_solution_part2());
+        assert_eq!(
+            cube_counts([("blue", 2)]),
+            p.games[0].get_min_cubes_needed()
+        );
+    }
+
+    #[test]
+    fn pick_equality_ignores_order() {
+        let drawn = Pick::in_order([("blue".to_string(), 3), ("red".to_string(), 4)]).unwrap();
+        assert_eq!(Pick::rgb(4, 0, 3), drawn);
+        assert_eq!(false, Pick::rgb(4, 0, 2) == drawn);
+        assert_eq!(
+            None,
+            Pick::in_order([("red".to_string(), 200), ("red".to_string(), 56)])
+        )
+    }
+
+    #[test]
+    fn text_round_trip() {
         let data = get_day_input(2);
-        let p = read_file_into_problem(&data);
-        let result = p.get_solution_part1(12, 13, 14);
-        assert_eq!(2727, result)
+        let p: Problem = data.parse().unwrap();
+        assert_eq!(data.trim_end(), p.to_string());
+        let reparsed: Problem = p.to_string().parse().unwrap();
+        assert_eq!(p, reparsed)
     }
+
     #[test]
-    fn day2_part_2_answer() {
+    fn json_format() {
+        let drawn = Pick::in_order([("green".to_string(), 2), ("blue".to_string(), 1)]).unwrap();
+        let p = Problem::new(vec![Game::new(3, vec![drawn, Pick::rgb(1, 0, 0)])]);
+        assert_eq!(
+            r#"{"games":[{"id":3,"picks":[{"green":2,"blue":1},{"red":1}]}]}"#,
+            p.to_json()
+        )
+    }
+
+    #[test]
+    fn json_round_trip() {
         let data = get_day_input(2);
-        let p = read_file_into_problem(&data);
-        let result = p.get_solution_part2();
-        assert_eq!(56580, result)
+        let p: Problem = data.parse().unwrap();
+        let from_json = Problem::from_json(&p.to_json()).unwrap();
+        assert_eq!(data.trim_end(), from_json.to_string());
+        assert_eq!(p, from_json)
+    }
+
+    #[test]
+    fn json_errors() {
+        let too_many = r#"{"games":[{"id":1,"picks":[{"red":200,"red":100}]}]}"#;
+        assert_eq!(
+            true,
+            matches!(Problem::from_json(too_many), Err(Error::Json { .. }))
+        );
+        let not_a_count = r#"{"games":[{"id":1,"picks":[{"red":-1}]}]}"#;
+        assert_eq!(
+            true,
+            matches!(Problem::from_json(not_a_count), Err(Error::Json { .. }))
+        );
     }
 }
@@ -0,0 +1,351 @@
+use std::collections::BTreeSet;
+
+use super::{CubeCounts, Game, Problem};
+
+/// What makes one bag better than another when searching for the best bag for a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Make as many games as possible.
+    PossibleGames,
+    /// Make the ids of the possible games add up to as much as possible, like part 1 does.
+    IdSum,
+}
+
+impl Objective {
+    fn score<'a>(self, games: impl Iterator<Item = &'a Needs>) -> u64 {
+        match self {
+            Objective::PossibleGames => games.count() as u64,
+            Objective::IdSum => games.map(|g| g.id as u64).sum(),
+        }
+    }
+}
+
+/// The bags that score best for a budget.
+#[derive(Debug, PartialEq)]
+pub struct BestBags {
+    pub score: u64,
+    /// Every distinct bag reaching the score. A bag only holds the cubes the games it makes possible
+    /// need, so cubes left over from the budget can be added in any color.
+    pub bags: Vec<CubeCounts>,
+}
+
+/// The fewest cubes of each color a game needs, indexed like the colors of the search.
+struct Needs {
+    id: u32,
+    cubes: Vec<u8>,
+}
+
+/// Searches through bags holding the colors of a set of games. Only the counts that some game needs
+/// are ever worth trying for a color, since a bag in between makes no more games possible than the
+/// count just below it. Counts are tried for every color but the last, and the best count for the
+/// last color is then worked out directly from the games the other colors still allow.
+struct Search {
+    colors: Vec<String>,
+    games: Vec<Needs>,
+}
+
+impl Search {
+    fn new<'a>(games: impl IntoIterator<Item = &'a Game>) -> Self {
+        let mins: Vec<_> = games
+            .into_iter()
+            .map(|g| (g.id, g.get_min_cubes_needed()))
+            .collect();
+        let colors: Vec<String> = mins
+            .iter()
+            .flat_map(|(_, min)| min.keys().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let games = mins
+            .iter()
+            .map(|(id, min)| Needs {
+                id: *id,
+                cubes: colors
+                    .iter()
+                    .map(|c| min.get(c).copied().unwrap_or(0))
+                    .collect(),
+            })
+            .collect();
+
+        Search { colors, games }
+    }
+
+    /// The color whose count is worked out from the others, if there are any colors at all.
+    fn last_color(&self) -> Option<usize> {
+        self.colors.len().checked_sub(1)
+    }
+
+    /// Calls `visit` for every choice of counts for all colors but the last that stays within the
+    /// budget, with the number of cubes spent and the games still possible.
+    fn for_each_prefix<'a>(
+        &'a self,
+        budget: Option<u32>,
+        visit: &mut impl FnMut(u32, &[&'a Needs]),
+    ) {
+        let games: Vec<_> = self.games.iter().collect();
+        self.extend_prefix(0, 0, &games, budget, visit);
+    }
+
+    fn extend_prefix<'a>(
+        &'a self,
+        color: usize,
+        spent: u32,
+        games: &[&'a Needs],
+        budget: Option<u32>,
+        visit: &mut impl FnMut(u32, &[&'a Needs]),
+    ) {
+        if color + 1 >= self.colors.len() {
+            visit(spent, games);
+            return;
+        }
+
+        let counts: BTreeSet<u8> = games.iter().map(|g| g.cubes[color]).chain([0]).collect();
+        for count in counts {
+            let spent = spent + count as u32;
+            if budget.is_some_and(|budget| spent > budget) {
+                break;
+            }
+            let allowed: Vec<_> = games
+                .iter()
+                .filter(|g| g.cubes[color] <= count)
+                .copied()
+                .collect();
+            self.extend_prefix(color + 1, spent, &allowed, budget, visit);
+        }
+    }
+
+    /// The smallest bag making all the games possible.
+    fn join(&self, games: &[&Needs]) -> Vec<u8> {
+        (0..self.colors.len())
+            .map(|color| games.iter().map(|g| g.cubes[color]).max().unwrap_or(0))
+            .collect()
+    }
+
+    fn to_counts(&self, bag: &[u8]) -> CubeCounts {
+        self.colors
+            .iter()
+            .zip(bag)
+            .filter(|(_, count)| **count > 0)
+            .map(|(color, count)| (color.clone(), *count))
+            .collect()
+    }
+}
+
+impl Problem {
+    /// The bags holding at most `budget` cubes in total that score best, looking only at the colors
+    /// drawn in the games. Takes time growing with the number of distinct counts per color raised to
+    /// the number of colors less one, which is quick for the three colors of the puzzle.
+    pub fn best_bags(&self, budget: u32, objective: Objective) -> BestBags {
+        let search = Search::new(&self.games);
+        let mut score = 0;
+        let mut bags = BTreeSet::new();
+        search.for_each_prefix(Some(budget), &mut |spent, games| {
+            let possible: Vec<_> = match search.last_color() {
+                Some(last) => {
+                    let left = (budget - spent).min(u8::MAX as u32) as u8;
+                    games
+                        .iter()
+                        .filter(|g| g.cubes[last] <= left)
+                        .copied()
+                        .collect()
+                }
+                None => games.to_vec(),
+            };
+
+            let found = objective.score(possible.iter().copied());
+            if found > score {
+                score = found;
+                bags.clear();
+            }
+            if found == score {
+                bags.insert(search.join(&possible));
+            }
+        });
+
+        BestBags {
+            score,
+            bags: bags.iter().map(|bag| search.to_counts(bag)).collect(),
+        }
+    }
+
+    /// The Pareto frontier of the smallest bags making at least `at_least` of the games with the
+    /// given ids possible: no other bag that does so has at most as many cubes of every color. When
+    /// all the games must be possible there is a single bag, the cubes each game needs at least.
+    /// Ids that are not in the problem are ignored, so there are no bags if fewer than `at_least`
+    /// games are left.
+    pub fn minimal_bags(&self, ids: &[u32], at_least: usize) -> Vec<CubeCounts> {
+        if at_least == 0 {
+            return vec![CubeCounts::new()];
+        }
+        let search = Search::new(self.games.iter().filter(|g| ids.contains(&g.id)));
+        if search.games.len() < at_least {
+            return Vec::new();
+        }
+
+        let mut candidates = BTreeSet::new();
+        search.for_each_prefix(None, &mut |_, games| {
+            if games.len() < at_least {
+                return;
+            }
+            let possible: Vec<_> = match search.last_color() {
+                Some(last) => {
+                    let mut counts: Vec<_> = games.iter().map(|g| g.cubes[last]).collect();
+                    counts.sort_unstable();
+                    let needed = counts[at_least.saturating_sub(1)];
+                    games
+                        .iter()
+                        .filter(|g| g.cubes[last] <= needed)
+                        .copied()
+                        .collect()
+                }
+                None => games.to_vec(),
+            };
+            candidates.insert(search.join(&possible));
+        });
+
+        candidates
+            .iter()
+            .filter(|bag| {
+                !candidates
+                    .iter()
+                    .any(|other| other != *bag && other.iter().zip(bag.iter()).all(|(o, b)| o <= b))
+            })
+            .map(|bag| search.to_counts(bag))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BestBags, Objective};
+    use crate::advent2023::day2::{cube_counts, read_file_into_problem, rgb, CubeCounts, Problem};
+    use crate::test::get_day_input;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    /// Best score of every bag within the budget, tried one by one.
+    fn brute_force(p: &Problem, budget: u32, objective: Objective) -> u64 {
+        let mut best = 0;
+        for red in 0..=budget.min(255) {
+            for green in 0..=(budget - red).min(255) {
+                let blue = (budget - red - green).min(255);
+                let bag = rgb(red as u8, green as u8, blue as u8);
+                let possible = p.games.iter().filter(|g| g.is_valid_for(&bag));
+                let score = match objective {
+                    Objective::PossibleGames => possible.count() as u64,
+                    Objective::IdSum => possible.map(|g| g.id as u64).sum(),
+                };
+                best = best.max(score);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn best_bags_matches_brute_force() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        for budget in [0, 5, 12, 20, 25, 30, 39, 45] {
+            for objective in [Objective::PossibleGames, Objective::IdSum] {
+                let best = p.best_bags(budget, objective);
+                assert_eq!(
+                    brute_force(&p, budget, objective),
+                    best.score,
+                    "budget {budget}, {objective:?}"
+                );
+                for bag in &best.bags {
+                    assert_eq!(true, bag.values().map(|c| *c as u32).sum::<u32>() <= budget);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn best_bags_every_game() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        assert_eq!(
+            BestBags {
+                score: 15,
+                bags: vec![rgb(20, 13, 15)],
+            },
+            p.best_bags(48, Objective::IdSum)
+        )
+    }
+
+    #[test]
+    fn best_bags_ties() {
+        let p = read_file_into_problem("Game 1: 5 red, 1 blue\nGame 2: 1 red, 5 blue").unwrap();
+        assert_eq!(
+            BestBags {
+                score: 1,
+                bags: vec![rgb(5, 0, 1), rgb(1, 0, 5)],
+            },
+            p.best_bags(8, Objective::PossibleGames)
+        );
+        assert_eq!(
+            BestBags {
+                score: 2,
+                bags: vec![rgb(1, 0, 5)],
+            },
+            p.best_bags(8, Objective::IdSum)
+        )
+    }
+
+    #[test]
+    fn best_bags_no_games_possible() {
+        let p = read_file_into_problem("Game 1: 5 red").unwrap();
+        assert_eq!(
+            BestBags {
+                score: 0,
+                bags: vec![cube_counts([])],
+            },
+            p.best_bags(4, Objective::PossibleGames)
+        )
+    }
+
+    #[test]
+    fn best_bags_beat_part_1_bag() {
+        let p = read_file_into_problem(&get_day_input(2)).unwrap();
+        let best = p.best_bags(12 + 13 + 14, Objective::IdSum);
+        assert_eq!(
+            true,
+            best.score >= p.get_solution_part1(&rgb(12, 13, 14)) as u64
+        );
+        assert_eq!(false, best.bags.is_empty())
+    }
+
+    #[test]
+    fn minimal_bags_for_every_game() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        assert_eq!(vec![rgb(4, 3, 6)], p.minimal_bags(&[1, 2], 2));
+        assert_eq!(vec![rgb(20, 13, 15)], p.minimal_bags(&[1, 2, 3, 4, 5], 5));
+    }
+
+    #[test]
+    fn minimal_bags_frontier() {
+        let p = read_file_into_problem(
+            "Game 1: 5 red, 1 blue\nGame 2: 1 red, 5 blue\nGame 3: 3 red; 3 blue",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![rgb(5, 0, 3), rgb(3, 0, 5)],
+            p.minimal_bags(&[1, 2, 3], 2)
+        );
+        assert_eq!(
+            vec![rgb(5, 0, 1), rgb(3, 0, 3), rgb(1, 0, 5)],
+            p.minimal_bags(&[1, 2, 3], 1)
+        );
+        assert_eq!(vec![rgb(5, 0, 5)], p.minimal_bags(&[1, 2, 3], 3));
+    }
+
+    #[test]
+    fn minimal_bags_unknown_ids() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        assert_eq!(Vec::<CubeCounts>::new(), p.minimal_bags(&[1, 9], 2));
+        assert_eq!(vec![rgb(4, 2, 6)], p.minimal_bags(&[1, 9], 1));
+        assert_eq!(vec![CubeCounts::new()], p.minimal_bags(&[], 0));
+    }
+}
@@ -0,0 +1,219 @@
+use super::{CubeCounts, Game, Pick, Problem};
+
+/// A candidate bag along with how likely it is to have given the draws, as a natural logarithm so
+/// that the likelihood of many games does not underflow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bag: CubeCounts,
+    pub log_likelihood: f64,
+}
+
+/// Natural logarithm of the number of ways to choose `k` items out of `n`.
+fn ln_choose(n: u32, k: u32) -> f64 {
+    (0..k).map(|i| ((n - i) as f64 / (i + 1) as f64).ln()).sum()
+}
+
+/// Every bag holding exactly `total` cubes of the given colors, with no more than 255 cubes of any
+/// one color. There are many of them for large totals or many colors, so keep both small.
+pub fn bags_with_total<'a>(
+    colors: impl IntoIterator<Item = &'a str>,
+    total: u32,
+) -> Vec<CubeCounts> {
+    let colors: Vec<_> = colors.into_iter().collect();
+    let mut bags = Vec::new();
+    fill_bags(&colors, total, &mut CubeCounts::new(), &mut bags);
+    bags
+}
+
+fn fill_bags(colors: &[&str], left: u32, bag: &mut CubeCounts, bags: &mut Vec<CubeCounts>) {
+    match colors {
+        [] if left == 0 => bags.push(bag.clone()),
+        [] => {}
+        [last] => {
+            if let Ok(count) = u8::try_from(left) {
+                let mut bag = bag.clone();
+                if count > 0 {
+                    bag.insert(last.to_string(), count);
+                }
+                bags.push(bag);
+            }
+        }
+        [color, rest @ ..] => {
+            for count in 0..=left.min(u8::MAX as u32) as u8 {
+                if count > 0 {
+                    bag.insert(color.to_string(), count);
+                }
+                fill_bags(rest, left - count as u32, bag, bags);
+                bag.remove(*color);
+            }
+        }
+    }
+}
+
+impl Pick {
+    /// How likely the bag is to give this pick when the cubes are drawn at once, so that no cube is
+    /// drawn twice. That is the multivariate hypergeometric probability of the pick: the number of
+    /// ways to draw its cubes of every color out of the bag over the number of ways to draw that many
+    /// cubes at all. Negative infinity when the bag can not give the pick.
+    pub fn log_likelihood(&self, bag: &CubeCounts) -> f64 {
+        let in_bag: u32 = bag.values().map(|c| *c as u32).sum();
+        let drawn: u32 = self.cubes().map(|(_, c)| c as u32).sum();
+        if !self.is_valid_for(bag) || drawn > in_bag {
+            return f64::NEG_INFINITY;
+        }
+
+        let ways: f64 = self
+            .cubes()
+            .map(|(color, count)| {
+                let in_bag = bag.get(color).copied().unwrap_or(0);
+                ln_choose(in_bag as u32, count as u32)
+            })
+            .sum();
+        ways - ln_choose(in_bag, drawn)
+    }
+}
+
+impl Game {
+    /// How likely the bag is to give every pick of the game. The cubes go back in the bag after each
+    /// pick, so the picks are independent of each other.
+    pub fn log_likelihood(&self, bag: &CubeCounts) -> f64 {
+        self.picks.iter().map(|p| p.log_likelihood(bag)).sum()
+    }
+}
+
+impl Problem {
+    /// How likely the bag is to give every game, assuming they were all played with the same bag.
+    pub fn log_likelihood(&self, bag: &CubeCounts) -> f64 {
+        self.games.iter().map(|g| g.log_likelihood(bag)).sum()
+    }
+
+    /// The candidate bags that could have given the games, most likely first. Bags that could not
+    /// have given them are left out, and bags that are just as likely keep the order they were given
+    /// in.
+    pub fn rank_bags(&self, candidates: impl IntoIterator<Item = CubeCounts>) -> Vec<Estimate> {
+        let mut ranked: Vec<_> = candidates
+            .into_iter()
+            .map(|bag| Estimate {
+                log_likelihood: self.log_likelihood(&bag),
+                bag,
+            })
+            .filter(|e| e.log_likelihood > f64::NEG_INFINITY)
+            .collect();
+        ranked.sort_by(|a, b| b.log_likelihood.total_cmp(&a.log_likelihood));
+        ranked
+    }
+
+    /// The maximum likelihood estimate of the bag among the candidates, or None if none of them
+    /// could have given the games.
+    pub fn most_likely_bag(
+        &self,
+        candidates: impl IntoIterator<Item = CubeCounts>,
+    ) -> Option<Estimate> {
+        self.rank_bags(candidates).into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bags_with_total, ln_choose};
+    use crate::advent2023::day2::{cube_counts, read_file_into_problem, rgb, Game, Pick};
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert_eq!(
+            true,
+            (expected - actual).abs() < 1e-9,
+            "{expected} != {actual}"
+        )
+    }
+
+    #[test]
+    fn choose() {
+        assert_close(0.0, ln_choose(5, 0));
+        assert_close(10f64.ln(), ln_choose(5, 2));
+        assert_close(0.0, ln_choose(7, 7));
+    }
+
+    #[test]
+    fn pick_hypergeometric() {
+        // 4 ways to draw a red and a blue out of 2 red and 2 blue, out of 6 ways to draw any 2
+        let pick = Pick::rgb(1, 0, 1);
+        assert_close((4.0f64 / 6.0).ln(), pick.log_likelihood(&rgb(2, 0, 2)));
+        // 3 red out of 3 red and 1 blue
+        assert_close(
+            (1.0f64 / 4.0).ln(),
+            Pick::rgb(3, 0, 0).log_likelihood(&rgb(3, 0, 1)),
+        );
+    }
+
+    #[test]
+    fn pick_impossible() {
+        let bag = rgb(2, 0, 2);
+        assert_eq!(f64::NEG_INFINITY, Pick::rgb(3, 0, 0).log_likelihood(&bag));
+        assert_eq!(f64::NEG_INFINITY, Pick::rgb(0, 1, 0).log_likelihood(&bag));
+        assert_eq!(f64::NEG_INFINITY, Pick::rgb(2, 0, 3).log_likelihood(&bag));
+    }
+
+    #[test]
+    fn game_multiplies_picks() {
+        let bag = rgb(2, 0, 2);
+        let game = Game::new(1, vec![Pick::rgb(1, 0, 1), Pick::rgb(2, 0, 0)]);
+        assert_close(
+            Pick::rgb(1, 0, 1).log_likelihood(&bag) + Pick::rgb(2, 0, 0).log_likelihood(&bag),
+            game.log_likelihood(&bag),
+        )
+    }
+
+    #[test]
+    fn bags_of_total() {
+        let bags = bags_with_total(["red", "green", "blue"], 4);
+        assert_eq!(15, bags.len());
+        for bag in &bags {
+            assert_eq!(4, bag.values().map(|c| *c as u32).sum::<u32>());
+        }
+        assert_eq!(vec![cube_counts([])], bags_with_total([], 0));
+        assert_eq!(0, bags_with_total(["red"], 256).len());
+        assert_eq!(2, bags_with_total(["red", "blue"], 509).len());
+    }
+
+    #[test]
+    fn most_likely_even_split() {
+        let p =
+            read_file_into_problem("Game 1: 1 red, 1 blue; 1 blue, 1 red\nGame 2: 1 red, 1 blue")
+                .unwrap();
+        let best = p
+            .most_likely_bag(bags_with_total(["red", "blue"], 4))
+            .unwrap();
+        assert_eq!(rgb(2, 0, 2), best.bag);
+    }
+
+    #[test]
+    fn most_likely_single_color() {
+        let p = read_file_into_problem("Game 1: 3 red; 3 red; 2 red").unwrap();
+        let ranked = p.rank_bags(bags_with_total(["red", "blue"], 4));
+        let bags: Vec<_> = ranked.iter().map(|e| e.bag.clone()).collect();
+        assert_eq!(vec![rgb(4, 0, 0), rgb(3, 0, 1)], bags);
+        assert_close(0.0, ranked[0].log_likelihood);
+    }
+
+    #[test]
+    fn ranked_example() {
+        let p = read_file_into_problem(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+        )
+        .unwrap();
+        let ranked = p.rank_bags(bags_with_total(["red", "green", "blue"], 16));
+        assert_eq!(false, ranked.is_empty());
+        for pair in ranked.windows(2) {
+            assert_eq!(true, pair[0].log_likelihood >= pair[1].log_likelihood);
+        }
+        // every bag left could have given the games, so it holds at least what they need
+        for estimate in &ranked {
+            assert_eq!(true, estimate.bag["red"] >= 4 && estimate.bag["blue"] >= 6);
+        }
+        assert_eq!(
+            Some(ranked[0].clone()),
+            p.most_likely_bag(bags_with_total(["red", "green", "blue"], 16))
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::num::{IntErrorKind, ParseIntError};
+use std::str::FromStr;
+
+use super::{Game, Pick};
+use crate::error::{Error, Result};
+
+/// Reads a single game following the grammar below, where spaces and tabs are allowed between any
+/// two tokens and a count must be separated from its color.
+///
+/// ```text
+/// game  = "Game" id ":" pick { ";" pick }
+/// pick  = cube { "," cube }
+/// cube  = count color
+/// color = letter { letter }
+/// ```
+pub fn parse_game(line_number: usize, line: &str) -> Result<Game> {
+    let mut cursor = Cursor {
+        line_number,
+        line: line.strip_suffix('\r').unwrap_or(line),
+        position: 0,
+    };
+
+    cursor.keyword("Game", "\"Game\"")?;
+    let id = cursor.number("a game id", "a game id of at most 4294967295")?;
+    cursor.symbol(':', "':' after the game id")?;
+
+    let mut picks = vec![cursor.pick()?];
+    while cursor.try_symbol(';') {
+        picks.push(cursor.pick()?);
+    }
+
+    cursor.skip_whitespace();
+    if !cursor.at_end() {
+        return Err(cursor.error("',', ';' or the end of the line"));
+    }
+
+    Ok(Game { id, picks })
+}
+
+/// Position within the line being parsed.
+struct Cursor<'a> {
+    line_number: usize,
+    line: &'a str,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.line[self.position..]
+    }
+
+    fn at_end(&self) -> bool {
+        self.position == self.line.len()
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let trimmed = self.rest().trim_start_matches([' ', '\t']);
+        let skipped = self.rest().len() - trimmed.len();
+        self.position += skipped;
+        skipped > 0
+    }
+
+    /// Error for the token starting at the current position, which runs until the next separator.
+    fn error(&self, expected: &'static str) -> Error {
+        let rest = self.rest();
+        let token_end = rest
+            .find([' ', '\t', ',', ';', ':'])
+            .map(|end| end.max(rest.chars().next().map_or(0, char::len_utf8)))
+            .unwrap_or(rest.len());
+        self.error_at(self.position, &rest[..token_end], expected)
+    }
+
+    fn error_at(&self, position: usize, token: &str, expected: &'static str) -> Error {
+        Error::Parse {
+            line: self.line_number,
+            column: self.line[..position].chars().count() + 1,
+            token: token.to_string(),
+            expected,
+            excerpt: self.line.to_string(),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, expected: &'static str) -> Result<()> {
+        self.skip_whitespace();
+        match self.rest().strip_prefix(keyword) {
+            Some(_) => {
+                self.position += keyword.len();
+                Ok(())
+            }
+            None => Err(self.error(expected)),
+        }
+    }
+
+    fn try_symbol(&mut self, symbol: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(symbol) {
+            self.position += symbol.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn symbol(&mut self, symbol: char, expected: &'static str) -> Result<()> {
+        if self.try_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Reads a number, reporting `too_large` when its digits do not fit in the type.
+    fn number<T: FromStr<Err = ParseIntError>>(
+        &mut self,
+        expected: &'static str,
+        too_large: &'static str,
+    ) -> Result<T> {
+        self.skip_whitespace();
+        let digits = self.rest().len()
+            - self
+                .rest()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return Err(self.error(expected));
+        }
+
+        let start = self.position;
+        let token = &self.line[start..start + digits];
+        self.position += digits;
+        token.parse().map_err(|e: ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow => self.error_at(start, token, too_large),
+            _ => self.error_at(start, token, expected),
+        })
+    }
+
+    fn color(&mut self) -> Result<&str> {
+        let start = self.position;
+        if !self.skip_whitespace() {
+            // back up over the count so "3red" is reported as a whole
+            let count_start = self.line[..start]
+                .rfind(|c: char| !c.is_ascii_digit())
+                .map_or(0, |i| i + 1);
+            let token = &self.line[count_start..];
+            let token_end = token.find([' ', '\t', ',', ';']).unwrap_or(token.len());
+            return Err(self.error_at(
+                count_start,
+                &token[..token_end],
+                "a space between the count and the color",
+            ));
+        }
+
+        let letters = self.rest().len() - self.rest().trim_start_matches(char::is_alphabetic).len();
+        let end = self.position + letters;
+        if letters == 0
+            || (end != self.line.len() && !self.line[end..].starts_with([' ', '\t', ',', ';']))
+        {
+            return Err(self.error("a color"));
+        }
+
+        let color = &self.line[self.position..end];
+        self.position = end;
+        Ok(color)
+    }
+
+    fn pick(&mut self) -> Result<Pick> {
+        let mut cubes: Vec<(String, u8)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            let count: u8 = self.number("a cube count", "a count of at most 255 cubes")?;
+            let token = &self.line[start..self.position];
+            let color = self.color()?;
+
+            // a color repeated within the pick is added up, which must still fit
+            let drawn: u16 = cubes
+                .iter()
+                .filter(|(c, _)| c == color)
+                .map(|(_, n)| *n as u16)
+                .sum();
+            if drawn + count as u16 > u8::MAX as u16 {
+                return Err(self.error_at(start, token, "at most 255 cubes of a color in a pick"));
+            }
+            cubes.push((color.to_string(), count));
+
+            if !self.try_symbol(',') {
+                return Ok(Pick::in_order(cubes).expect("counts checked while reading"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_game;
+    use crate::advent2023::day2::{cube_counts, rgb, Game, Pick};
+    use crate::error::Error;
+
+    fn parse_error(column: usize, token: &str, expected: &'static str, line: &str) -> Error {
+        Error::Parse {
+            line: 7,
+            column,
+            token: token.to_string(),
+            expected,
+            excerpt: line.to_string(),
+        }
+    }
+
+    #[test]
+    fn extra_whitespace_and_crlf() {
+        let result = parse_game(
+            7,
+            "  Game\t12 :3 blue ,4 red;  1 red\t, 2 green ; 2 green \r",
+        );
+        let expected = Game {
+            id: 12,
+            picks: vec![Pick::rgb(4, 0, 3), Pick::rgb(1, 2, 0), Pick::rgb(0, 2, 0)],
+        };
+        assert_eq!(Ok(expected), result)
+    }
+
+    #[test]
+    fn missing_id() {
+        let line = "Game : 3 blue";
+        assert_eq!(
+            Err(parse_error(6, ":", "a game id", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn missing_colon() {
+        let line = "Game 1 3 blue";
+        assert_eq!(
+            Err(parse_error(8, "3", "':' after the game id", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn not_a_game() {
+        let line = "Gaem 1: 3 blue";
+        assert_eq!(
+            Err(parse_error(1, "Gaem", "\"Game\"", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn trailing_comma() {
+        let line = "Game 1: 3 blue,";
+        assert_eq!(
+            Err(parse_error(16, "", "a cube count", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn empty_pick() {
+        let line = "Game 1: 3 blue;; 1 red";
+        assert_eq!(
+            Err(parse_error(16, ";", "a cube count", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn count_joined_to_color() {
+        let line = "Game 1: 3 blue, 3red";
+        assert_eq!(
+            Err(parse_error(
+                17,
+                "3red",
+                "a space between the count and the color",
+                line
+            )),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn color_with_digits() {
+        let line = "Game 1: 3 r3d";
+        assert_eq!(
+            Err(parse_error(11, "r3d", "a color", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn unicode_colors_and_columns() {
+        assert_eq!(
+            Ok(Game {
+                id: 1,
+                picks: vec![Pick::new(cube_counts([("grün", 3)]))],
+            }),
+            parse_game(7, "Game 1: 3 grün")
+        );
+
+        let line = "Game 1: 3 grün, x";
+        assert_eq!(
+            Err(parse_error(17, "x", "a cube count", line)),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn trailing_garbage() {
+        let line = "Game 1: 3 blue 4 red";
+        assert_eq!(
+            Err(parse_error(
+                16,
+                "4",
+                "',', ';' or the end of the line",
+                line
+            )),
+            parse_game(7, line)
+        )
+    }
+
+    #[test]
+    fn count_overflow() {
+        let line = "Game 1: 256 red";
+        assert_eq!(
+            Err(parse_error(9, "256", "a count of at most 255 cubes", line)),
+            parse_game(7, line)
+        );
+        let line = "Game 1: 200 red, 1 blue,  100 red";
+        assert_eq!(
+            Err(parse_error(
+                27,
+                "100",
+                "at most 255 cubes of a color in a pick",
+                line
+            )),
+            parse_game(7, line)
+        );
+        let line = "Game 4294967296: 1 red";
+        assert_eq!(
+            Err(parse_error(
+                6,
+                "4294967296",
+                "a game id of at most 4294967295",
+                line
+            )),
+            parse_game(7, line)
+        );
+        assert_eq!(
+            Ok(Game {
+                id: 1,
+                picks: vec![Pick::new(rgb(255, 0, 0))],
+            }),
+            parse_game(7, "Game 1: 255 red")
+        )
+    }
+}
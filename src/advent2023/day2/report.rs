@@ -0,0 +1,224 @@
+use serde::Serialize;
+
+use super::{power, CubeCounts, Problem};
+
+/// What the solution sees of a single game.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GameReport {
+    pub id: u32,
+    /// Number of picks in the game.
+    pub picks: usize,
+    /// The fewest cubes of each color the bag could have held for the game to be possible.
+    pub min_cubes: CubeCounts,
+    /// The cubes of every color of the problem in `min_cubes` multiplied together, as added up by
+    /// part 2. None when it does not fit in a u64, and then left empty in CSV and tables.
+    pub power: Option<u64>,
+    /// Whether the game is possible with the bag the report was made for, as counted by part 1.
+    pub valid: bool,
+}
+
+/// A line for every game of a problem, to see how each one adds to the answers. It can be written
+/// out as CSV, JSON or a text table, all with the games in the order of the input.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// Every color drawn in the problem, which are the columns of the minimum cubes.
+    pub colors: Vec<String>,
+    pub games: Vec<GameReport>,
+}
+
+impl Problem {
+    /// Reports on every game, checking whether it is possible with the given bag.
+    pub fn report(&self, bag: &CubeCounts) -> Report {
+        let colors = self.colors();
+        let games = self
+            .games
+            .iter()
+            .map(|g| {
+                let min_cubes = g.get_min_cubes_needed();
+                GameReport {
+                    id: g.id,
+                    picks: g.picks.len(),
+                    power: power(&min_cubes, &colors),
+                    valid: g.is_valid_for(bag),
+                    min_cubes,
+                }
+            })
+            .collect();
+
+        Report {
+            colors: colors.into_iter().map(str::to_string).collect(),
+            games,
+        }
+    }
+}
+
+impl Report {
+    /// Column names followed by a row of values for every game.
+    fn rows(&self) -> Vec<Vec<String>> {
+        let header = ["id".to_string(), "picks".to_string()]
+            .into_iter()
+            .chain(self.colors.iter().map(|c| format!("min {c}")))
+            .chain(["power".to_string(), "valid".to_string()])
+            .collect();
+        let games = self.games.iter().map(|g| {
+            [g.id.to_string(), g.picks.to_string()]
+                .into_iter()
+                .chain(
+                    self.colors
+                        .iter()
+                        .map(|c| g.min_cubes.get(c).copied().unwrap_or(0).to_string()),
+                )
+                .chain([
+                    g.power.map_or(String::new(), |p| p.to_string()),
+                    g.valid.to_string(),
+                ])
+                .collect()
+        });
+
+        std::iter::once(header).chain(games).collect()
+    }
+
+    /// The report as CSV with a header line, quoting the fields that need it.
+    pub fn to_csv(&self) -> String {
+        self.rows()
+            .iter()
+            .map(|row| {
+                let fields: Vec<_> = row.iter().map(|f| csv_field(f)).collect();
+                fields.join(",") + "\n"
+            })
+            .collect()
+    }
+
+    /// The report as a JSON document holding the colors and the games.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("reports always serialize")
+    }
+
+    /// The report as a text table with the columns lined up to the right.
+    pub fn to_table(&self) -> String {
+        let rows = self.rows();
+        let widths: Vec<_> = (0..rows[0].len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        rows.iter()
+            .map(|row| {
+                let cells: Vec<_> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:>width$}"))
+                    .collect();
+                cells.join("  ") + "\n"
+            })
+            .collect()
+    }
+}
+
+/// Quotes a CSV field holding a separator, a quote or a line break, doubling any quotes in it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advent2023::day2::{
+        cube_counts, read_file_into_problem, rgb, Game, GameReport, Pick, Problem,
+    };
+    use crate::test::get_day_input;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+
+    #[test]
+    fn report_games() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        let report = p.report(&rgb(12, 13, 14));
+        assert_eq!(vec!["blue", "green", "red"], report.colors);
+        assert_eq!(
+            GameReport {
+                id: 3,
+                picks: 3,
+                min_cubes: rgb(20, 13, 6),
+                power: Some(1560),
+                valid: false,
+            },
+            report.games[2]
+        );
+        let valid: Vec<_> = report.games.iter().map(|g| g.valid).collect();
+        assert_eq!(vec![true, true, false], valid);
+    }
+
+    #[test]
+    fn report_adds_up_to_answers() {
+        let p = read_file_into_problem(&get_day_input(2)).unwrap();
+        let report = p.report(&rgb(12, 13, 14));
+        assert_eq!(
+            p.get_solution_part2(),
+            Ok(report.games.iter().map(|g| g.power.unwrap()).sum::<u64>())
+        );
+        assert_eq!(
+            p.get_solution_part1(&rgb(12, 13, 14)),
+            report
+                .games
+                .iter()
+                .filter(|g| g.valid)
+                .map(|g| g.id)
+                .sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn csv() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        assert_eq!(
+            "id,picks,min blue,min green,min red,power,valid
+1,3,6,2,4,48,true
+2,3,4,3,1,12,true
+3,3,6,13,20,1560,false
+",
+            p.report(&rgb(12, 13, 14)).to_csv()
+        )
+    }
+
+    #[test]
+    fn csv_quotes_colors() {
+        let pick = Pick::new(cube_counts([("red, \"dark\"", 2)]));
+        let p = Problem::new(vec![Game::new(1, vec![pick])]);
+        assert_eq!(
+            "id,picks,\"min red, \"\"dark\"\"\",power,valid\n1,1,2,2,true\n",
+            p.report(&cube_counts([("red, \"dark\"", 2)])).to_csv()
+        )
+    }
+
+    #[test]
+    fn json() {
+        let p = read_file_into_problem("Game 1: 3 blue, 4 red; 1 red, 2 green").unwrap();
+        assert_eq!(
+            r#"{"colors":["blue","green","red"],"games":[{"id":1,"picks":2,"min_cubes":{"blue":3,"green":2,"red":4},"power":24,"valid":false}]}"#,
+            p.report(&rgb(12, 1, 14)).to_json()
+        )
+    }
+
+    #[test]
+    fn table() {
+        let p = read_file_into_problem(EXAMPLE).unwrap();
+        assert_eq!(
+            "id  picks  min blue  min green  min red  power  valid
+ 1      3         6          2        4     48   true
+ 2      3         4          3        1     12   true
+ 3      3         6         13       20   1560  false
+",
+            p.report(&rgb(12, 13, 14)).to_table()
+        )
+    }
+}
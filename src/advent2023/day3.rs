@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use super::grid::{Grid, Pos, Span};
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Day 3: Gear Ratios
+pub struct GearRatios;
+
+impl Solution for GearRatios {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    type Input = Schematic;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.part_numbers().map(|n| n.value as u64).sum())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.gears().iter().map(|g| g.ratio()).sum())
+    }
+}
+
+/// A number drawn in the schematic along with the cells its digits cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub span: Span,
+}
+
+/// A `*` next to exactly two numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub pos: Pos,
+    pub numbers: [u32; 2],
+}
+
+impl Gear {
+    /// The two numbers multiplied together.
+    pub fn ratio(&self) -> u64 {
+        self.numbers[0] as u64 * self.numbers[1] as u64
+    }
+}
+
+/// The engine schematic: a grid of digits, periods for empty cells and symbols for everything else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    grid: Grid<char>,
+    numbers: Vec<Number>,
+}
+
+impl Schematic {
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    /// Every number in the schematic, top to bottom and left to right.
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    /// The numbers next to a symbol, including diagonally, which are the part numbers.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|n| self.grid.around(&n.span).any(|p| is_symbol(self.grid[p])))
+    }
+
+    /// Every `*` next to exactly two numbers, top to bottom and left to right. Each number adds
+    /// itself to the stars around it, so the schematic is only scanned once.
+    pub fn gears(&self) -> Vec<Gear> {
+        let mut stars: BTreeMap<Pos, Vec<u32>> = BTreeMap::new();
+        for n in &self.numbers {
+            for p in self.grid.around(&n.span) {
+                if self.grid[p] == '*' {
+                    stars.entry(p).or_default().push(n.value);
+                }
+            }
+        }
+
+        stars
+            .into_iter()
+            .filter_map(|(pos, numbers)| match numbers[..] {
+                [a, b] => Some(Gear {
+                    pos,
+                    numbers: [a, b],
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+impl std::str::FromStr for Schematic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let grid: Grid<char> = s.parse()?;
+        let numbers = grid
+            .row_spans(char::is_ascii_digit)
+            .into_iter()
+            .map(|span| {
+                let digits: String = span.positions().map(|p| grid[p]).collect();
+                match digits.parse() {
+                    Ok(value) => Ok(Number { value, span }),
+                    Err(_) => Err(Error::Overflow {
+                        line: span.rows.start + 1,
+                    }),
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Schematic { grid, numbers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gear, GearRatios, Schematic};
+    use crate::advent2023::grid::Pos;
+    use crate::advent2023::Solution;
+    use crate::error::Error;
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    /// Straightforward reading of the puzzle to check the solution against: every digit is looked
+    /// at on its own, and a number is a part number if any of its digits touches a symbol.
+    fn naive_part_1(input: &str) -> u64 {
+        let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+        let symbol_near = |row: usize, col: usize| {
+            (row.saturating_sub(1)..=row + 1).any(|r| {
+                (col.saturating_sub(1)..=col + 1).any(|c| {
+                    lines
+                        .get(r)
+                        .and_then(|l| l.get(c))
+                        .is_some_and(|c| *c != '.' && !c.is_ascii_digit())
+                })
+            })
+        };
+
+        let mut sum = 0;
+        for (row, line) in lines.iter().enumerate() {
+            let (mut value, mut part) = (0, false);
+            for col in 0..=line.len() {
+                match line.get(col).and_then(|c| c.to_digit(10)) {
+                    Some(digit) => {
+                        value = value * 10 + digit as u64;
+                        part |= symbol_near(row, col);
+                    }
+                    None => {
+                        if part {
+                            sum += value;
+                        }
+                        (value, part) = (0, false);
+                    }
+                }
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn numbers() {
+        let s: Schematic = EXAMPLE.parse().unwrap();
+        let values: Vec<_> = s.numbers().iter().map(|n| n.value).collect();
+        assert_eq!(vec![467, 114, 35, 633, 617, 58, 592, 755, 664, 598], values);
+        assert_eq!(0..3, s.numbers()[0].span.cols);
+    }
+
+    #[test]
+    fn part_numbers() {
+        let s: Schematic = EXAMPLE.parse().unwrap();
+        let parts: Vec<_> = s.part_numbers().map(|n| n.value).collect();
+        assert_eq!(vec![467, 35, 633, 617, 592, 755, 664, 598], parts);
+    }
+
+    #[test]
+    fn gears() {
+        let s: Schematic = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            vec![
+                Gear {
+                    pos: Pos::new(1, 3),
+                    numbers: [467, 35]
+                },
+                Gear {
+                    pos: Pos::new(8, 5),
+                    numbers: [755, 598]
+                },
+            ],
+            s.gears()
+        );
+    }
+
+    #[test]
+    fn solution() {
+        let s = GearRatios::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(4361), GearRatios::part_1(&s));
+        assert_eq!(Ok(467835), GearRatios::part_2(&s));
+        assert_eq!(4361, naive_part_1(EXAMPLE));
+    }
+
+    #[test]
+    fn numbers_on_the_edges() {
+        // numbers touching the borders and symbols only diagonally across a corner
+        let s: Schematic = "12.\n..#\n7.8".parse().unwrap();
+        let parts: Vec<_> = s.part_numbers().map(|n| n.value).collect();
+        assert_eq!(vec![12, 8], parts);
+        assert_eq!(Ok(20), GearRatios::part_1(&s));
+    }
+
+    #[test]
+    fn star_needs_exactly_two_numbers() {
+        let s: Schematic = "1.2\n.*.\n3..\n".parse().unwrap();
+        assert_eq!(Vec::<Gear>::new(), s.gears());
+        let s: Schematic = "1*2*3".parse().unwrap();
+        let ratios: Vec<_> = s.gears().iter().map(|g| g.ratio()).collect();
+        assert_eq!(vec![2, 6], ratios);
+    }
+
+    #[test]
+    fn other_symbols_are_parts_not_gears() {
+        let s: Schematic = "10%\n...\n#.5\n..+".parse().unwrap();
+        let parts: Vec<_> = s.part_numbers().map(|n| n.value).collect();
+        assert_eq!(vec![10, 5], parts);
+        assert_eq!(Ok(0), GearRatios::part_2(&s));
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(
+            Err(Error::Overflow { line: 2 }),
+            "...........\n99999999999".parse::<Schematic>().map(|_| ())
+        );
+        assert_eq!(
+            true,
+            matches!(
+                "...\n..".parse::<Schematic>(),
+                Err(Error::Parse { line: 2, .. })
+            )
+        );
+    }
+}
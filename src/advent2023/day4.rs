@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Day 4: Scratchcards
+pub struct Scratchcards;
+
+impl Solution for Scratchcards {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    type Input = Cards;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        input.points()
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        input.total_cards()
+    }
+}
+
+/// A single scratchcard with the winning numbers on the left of the bar and the numbers the card
+/// has on the right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: Vec<u32>,
+    pub have: Vec<u32>,
+}
+
+impl Card {
+    pub fn new(id: u32, winning: Vec<u32>, have: Vec<u32>) -> Self {
+        Card { id, winning, have }
+    }
+
+    /// How many of the numbers the card has are winning numbers.
+    pub fn matches(&self) -> usize {
+        let winning: HashSet<_> = self.winning.iter().collect();
+        self.have.iter().filter(|n| winning.contains(n)).count()
+    }
+
+    /// One point for the first match, doubled for every match after it. None when the points do
+    /// not fit in a u64.
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            m => 1u64.checked_shl(m as u32 - 1),
+        }
+    }
+}
+
+/// Every card of the pile, in order. The matches of each card are counted once when the pile is
+/// made, as both parts only need those.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cards {
+    cards: Vec<Card>,
+    matches: Vec<usize>,
+    /// The line each card was read from, which errors are reported on.
+    lines: Vec<usize>,
+}
+
+impl Cards {
+    /// The pile of cards, numbering their lines from 1 as if each was written on a line of its own.
+    pub fn new(cards: Vec<Card>) -> Self {
+        let lines = (1..=cards.len()).collect();
+        Cards::with_lines(cards, lines)
+    }
+
+    fn with_lines(cards: Vec<Card>, lines: Vec<usize>) -> Self {
+        let matches = cards.iter().map(Card::matches).collect();
+        Cards {
+            cards,
+            matches,
+            lines,
+        }
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Sum of the points of every card, as asked by part 1.
+    pub fn points(&self) -> Result<u64> {
+        self.cards
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |sum, (index, card)| {
+                card.points()
+                    .and_then(|points| sum.checked_add(points))
+                    .ok_or(Error::Overflow {
+                        line: self.lines[index],
+                    })
+            })
+    }
+
+    /// How many of each card there are once every card has won its copies, in the order of the
+    /// cards. A card with `m` matches wins a copy of each of the next `m` cards for every copy of it,
+    /// and copies past the last card are never won.
+    ///
+    /// Takes time linear in the number of cards however many matches they have: rather than adding
+    /// the copies to every card in the range they are added once when the range starts and taken
+    /// off again where it ends.
+    pub fn copies(&self) -> Result<Vec<u64>> {
+        let n = self.cards.len();
+        let mut ending = vec![0u64; n + 1];
+        let mut won = 0u64;
+        let mut copies = Vec::with_capacity(n);
+        for (index, matches) in self.matches.iter().enumerate() {
+            won -= ending[index];
+            let overflow = Error::Overflow {
+                line: self.lines[index],
+            };
+            let count = won.checked_add(1).ok_or(overflow.clone())?;
+            copies.push(count);
+
+            let end = (index + 1 + matches).min(n);
+            if end > index + 1 {
+                won = won.checked_add(count).ok_or(overflow)?;
+                ending[end] += count;
+            }
+        }
+        Ok(copies)
+    }
+
+    /// Total number of cards including every copy won, as asked by part 2.
+    pub fn total_cards(&self) -> Result<u64> {
+        self.copies()?
+            .iter()
+            .zip(&self.lines)
+            .try_fold(0u64, |sum, (c, line)| {
+                sum.checked_add(*c).ok_or(Error::Overflow { line: *line })
+            })
+    }
+}
+
+impl FromStr for Cards {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (lines, cards) = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| Ok((index + 1, read_card(index + 1, line)?)))
+            .collect::<Result<(Vec<_>, Vec<_>)>>()?;
+        Ok(Cards::with_lines(cards, lines))
+    }
+}
+
+/// Reads a line such as "Card 1: 41 48 83 | 83 86  6", where any amount of spaces may separate the
+/// numbers.
+pub fn read_card(line_number: usize, line: &str) -> Result<Card> {
+    let error = |token: &str, expected| Error::field(line_number, line, token, expected);
+
+    let Some(rest) = line.strip_prefix("Card") else {
+        return Err(error(line, "\"Card\""));
+    };
+    let Some((id, numbers)) = rest.split_once(':') else {
+        return Err(error(rest, "':' after the card id"));
+    };
+    let id = id.trim();
+    let id = id.parse().map_err(|_| error(id, "a card id"))?;
+    let Some((winning, have)) = numbers.split_once('|') else {
+        return Err(error(numbers, "'|' between the numbers"));
+    };
+
+    let numbers = |text: &str| {
+        text.split_whitespace()
+            .map(|n| n.parse().map_err(|_| error(n, "a number")))
+            .collect::<Result<Vec<u32>>>()
+    };
+    Ok(Card::new(id, numbers(winning)?, numbers(have)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::{read_card, Card, Cards, Scratchcards};
+    use crate::advent2023::Solution;
+    use crate::error::Error;
+
+    const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    /// Plays out the cascade one card at a time, which is slow but obviously right.
+    fn naive_total(cards: &Cards) -> u64 {
+        let matches: Vec<_> = cards.cards().iter().map(Card::matches).collect();
+        let mut pile: VecDeque<_> = (0..matches.len()).collect();
+        let mut total = 0;
+        while let Some(index) = pile.pop_front() {
+            total += 1;
+            pile.extend((index + 1..=index + matches[index]).filter(|i| *i < matches.len()));
+        }
+        total
+    }
+
+    #[test]
+    fn read_example_card() {
+        assert_eq!(
+            Ok(Card::new(
+                3,
+                vec![1, 21, 53, 59, 44],
+                vec![69, 82, 63, 72, 16, 21, 14, 1]
+            )),
+            read_card(3, "Card   3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1")
+        )
+    }
+
+    #[test]
+    fn read_bad_cards() {
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 13,
+                token: "x".to_string(),
+                expected: "a number",
+                excerpt: "Card 1: 4 5 x | 1".to_string(),
+            }),
+            read_card(2, "Card 1: 4 5 x | 1")
+        );
+        assert_eq!(
+            true,
+            matches!(
+                read_card(1, "Card 1: 4 5 1"),
+                Err(Error::Parse {
+                    expected: "'|' between the numbers",
+                    ..
+                })
+            )
+        );
+        assert_eq!(
+            true,
+            matches!(
+                read_card(1, "Game 1: 4 | 1"),
+                Err(Error::Parse { column: 1, .. })
+            )
+        );
+    }
+
+    #[test]
+    fn matches_and_points() {
+        let cards: Cards = EXAMPLE.parse().unwrap();
+        let matches: Vec<_> = cards.cards().iter().map(Card::matches).collect();
+        assert_eq!(vec![4, 2, 2, 1, 0, 0], matches);
+        let points: Vec<_> = cards.cards().iter().map(|c| c.points().unwrap()).collect();
+        assert_eq!(vec![8, 2, 2, 1, 0, 0], points);
+    }
+
+    #[test]
+    fn copies() {
+        let cards: Cards = EXAMPLE.parse().unwrap();
+        assert_eq!(Ok(vec![1, 2, 4, 8, 14, 1]), cards.copies());
+    }
+
+    #[test]
+    fn solution() {
+        let cards = Scratchcards::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(13), Scratchcards::part_1(&cards));
+        assert_eq!(Ok(30), Scratchcards::part_2(&cards));
+        assert_eq!(30, naive_total(&cards));
+    }
+
+    #[test]
+    fn copies_stop_at_last_card() {
+        let card =
+            |id, matches: u32| Card::new(id, (1..=matches).collect(), (1..=matches).collect());
+        let cards = Cards::new(vec![card(1, 5), card(2, 1), card(3, 0)]);
+        assert_eq!(Ok(vec![1, 2, 4]), cards.copies());
+        assert_eq!(naive_total(&cards), cards.total_cards().unwrap());
+        assert_eq!(Ok(vec![]), Cards::new(vec![]).copies());
+    }
+
+    #[test]
+    fn agrees_with_cascade() {
+        // matches drawn from a fixed pseudo random sequence
+        let mut seed = 7u32;
+        let cards: Vec<_> = (1..=200)
+            .map(|id| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                let matches = (seed >> 16) % 4;
+                Card::new(id, (0..matches).collect(), (0..5).collect())
+            })
+            .collect();
+        let cards = Cards::new(cards);
+        assert_eq!(Ok(naive_total(&cards)), cards.total_cards());
+    }
+
+    #[test]
+    fn overflow() {
+        let card = Card::new(1, (0..65).collect(), (0..65).collect());
+        assert_eq!(None, card.points());
+        assert_eq!(
+            Err(Error::Overflow { line: 1 }),
+            Cards::new(vec![card]).points()
+        );
+
+        // every card wins a copy of every card after it, doubling the count each time
+        let cards = Cards::new(
+            (1..=70)
+                .map(|id| Card::new(id, (0..70).collect(), (0..70).collect()))
+                .collect(),
+        );
+        assert_eq!(true, matches!(cards.copies(), Err(Error::Overflow { .. })));
+
+        // errors are reported on the line the card was read from
+        let numbers: Vec<_> = (0..65).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let input = format!("Card 1: 1 | 2\n\nCard 2: {numbers} | {numbers}\n");
+        let cards: Cards = input.parse().unwrap();
+        assert_eq!(Err(Error::Overflow { line: 3 }), cards.points());
+    }
+
+    #[test]
+    fn million_cards() {
+        let cards = Cards::new(
+            (1..=1_000_000)
+                .map(|id| Card::new(id, vec![1, 2, 3], vec![3, 4, 5]))
+                .collect(),
+        );
+        let copies = cards.copies().unwrap();
+        // every card is won once by each copy of the card before it
+        assert_eq!(1, copies[0]);
+        assert_eq!(1_000_000, copies[999_999]);
+        assert_eq!(Ok(500_000_500_000), cards.total_cards());
+    }
+}
@@ -0,0 +1,367 @@
+use std::str::FromStr;
+
+use super::interval::{Interval, IntervalSet, OffsetMap};
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Day 5: If You Give A Seed A Fertilizer
+pub struct Fertilizer;
+
+impl Solution for Fertilizer {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    type Input = Almanac;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        let chain = input.chain();
+        Ok(input
+            .seeds()
+            .iter()
+            .map(|seed| chain.map(*seed))
+            .min()
+            .expect("an almanac is only read with seeds"))
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        let seeds = input.seed_ranges()?;
+        Ok(input
+            .chain()
+            .map_set(&seeds)
+            .min()
+            .expect("the seed ranges are never empty"))
+    }
+}
+
+/// One of the maps of the almanac, such as the one from seed to soil.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    pub from: String,
+    pub to: String,
+    pub map: OffsetMap,
+}
+
+/// The seeds to be planted and the maps leading from seed to location, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    /// The number and text of the line the seeds were read from, as part 2 reads them again.
+    seeds_line: (usize, String),
+    stages: Vec<Stage>,
+}
+
+impl Almanac {
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// The seed numbers read as pairs of a start and a length, as part 2 does. Fails on an odd
+    /// number of seeds, a range without seeds or one that would go past the last number.
+    pub fn seed_ranges(&self) -> Result<IntervalSet> {
+        let (line_number, line) = &self.seeds_line;
+        let error = |token: &str, expected| Error::field(*line_number, line, token, expected);
+        let tokens: Vec<_> = line["seeds:".len()..].split_whitespace().collect();
+        if self.seeds.len() % 2 == 1 {
+            let end = line.trim_end().len();
+            return Err(error(
+                &line[end..end],
+                "the length of the last range of seeds",
+            ));
+        }
+
+        self.seeds
+            .chunks(2)
+            .zip(tokens.chunks(2))
+            .map(|(pair, tokens)| match pair[1] {
+                0 => Err(error(tokens[1], "a range of at least one seed")),
+                len => {
+                    Interval::with_len(pair[0], len).ok_or(Error::Overflow { line: *line_number })
+                }
+            })
+            .collect()
+    }
+
+    /// Every stage composed into a single map from the first category to the last.
+    pub fn chain(&self) -> OffsetMap {
+        self.stages
+            .iter()
+            .fold(OffsetMap::identity(), |chain, stage| chain.then(&stage.map))
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s.lines().enumerate().map(|(index, line)| (index + 1, line));
+
+        let (line_number, line) = lines.next().unwrap_or((1, ""));
+        let error = |token: &str, expected| Error::field(line_number, line, token, expected);
+        let Some(seeds) = line.strip_prefix("seeds:") else {
+            return Err(error(line, "\"seeds:\""));
+        };
+        let seeds = seeds
+            .split_whitespace()
+            .map(|n| n.parse().map_err(|_| error(n, "a seed number")))
+            .collect::<Result<Vec<u64>>>()?;
+        if seeds.is_empty() {
+            let end = line.trim_end().len();
+            return Err(error(&line[end..end], "a seed number"));
+        }
+        let seeds_line = (line_number, line.to_string());
+
+        let mut stages: Vec<Stage> = Vec::new();
+        for (line_number, line) in lines {
+            let error = |token: &str, expected| Error::field(line_number, line, token, expected);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_suffix(" map:") {
+                let Some((from, to)) = name.split_once("-to-") else {
+                    return Err(error(name, "a map name such as \"seed-to-soil\""));
+                };
+                if stages.last().is_some_and(|s| s.to != from) {
+                    return Err(error(from, "the category the previous map leads to"));
+                }
+                stages.push(Stage {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    map: OffsetMap::identity(),
+                });
+                continue;
+            }
+
+            let Some(stage) = stages.last_mut() else {
+                return Err(error(line, "a map name such as \"seed-to-soil map:\""));
+            };
+            let numbers = line
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| error(n, "a number")))
+                .collect::<Result<Vec<u64>>>()?;
+            let [dest, source, len] = numbers[..] else {
+                return Err(error(line, "a destination, a source and a length"));
+            };
+            if !stage.map.insert(dest, source, len) {
+                return Err(error(line, "a range that does not overlap the ones before"));
+            }
+        }
+
+        Ok(Almanac {
+            seeds,
+            seeds_line,
+            stages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Almanac, Fertilizer};
+    use crate::advent2023::interval::{Interval, IntervalSet};
+    use crate::advent2023::Solution;
+    use crate::error::Error;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn read_almanac() {
+        let a: Almanac = EXAMPLE.parse().unwrap();
+        assert_eq!(vec![79, 14, 55, 13], a.seeds());
+        let names: Vec<_> = a.stages().iter().map(|s| (&*s.from, &*s.to)).collect();
+        assert_eq!(("seed", "soil"), names[0]);
+        assert_eq!(("humidity", "location"), names[6]);
+        assert_eq!(7, names.len());
+    }
+
+    #[test]
+    fn each_stage() {
+        let a: Almanac = EXAMPLE.parse().unwrap();
+        // seed 79, soil 81, fertilizer 81, water 81, light 74, temperature 78, humidity 78,
+        // location 82
+        let mut value = 79;
+        let mut path = vec![value];
+        for stage in a.stages() {
+            value = stage.map.map(value);
+            path.push(value);
+        }
+        assert_eq!(vec![79, 81, 81, 81, 74, 78, 78, 82], path);
+    }
+
+    #[test]
+    fn chain_matches_every_stage() {
+        let a: Almanac = EXAMPLE.parse().unwrap();
+        let chain = a.chain();
+        for seed in 0..200 {
+            let stepped = a.stages().iter().fold(seed, |v, s| s.map.map(v));
+            assert_eq!(stepped, chain.map(seed), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn solution() {
+        let a = Fertilizer::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(35), Fertilizer::part_1(&a));
+        assert_eq!(Ok(46), Fertilizer::part_2(&a));
+    }
+
+    #[test]
+    fn seed_ranges() {
+        let a: Almanac = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            Ok([Interval::new(55, 68), Interval::new(79, 93)]
+                .into_iter()
+                .collect::<IntervalSet>()),
+            a.seed_ranges()
+        );
+        assert_eq!(
+            Err(Error::Overflow { line: 1 }),
+            "seeds: 18446744073709551615 1"
+                .parse::<Almanac>()
+                .unwrap()
+                .seed_ranges()
+        );
+
+        // part 1 takes the seeds one at a time, so only part 2 needs them in pairs
+        let odd: Almanac = "seeds: 1  2 3 ".parse().unwrap();
+        assert_eq!(Ok(1), Fertilizer::part_1(&odd));
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 14,
+                token: String::new(),
+                expected: "the length of the last range of seeds",
+                excerpt: "seeds: 1  2 3 ".to_string(),
+            }),
+            Fertilizer::part_2(&odd)
+        );
+
+        let zero_length: Almanac = "seeds: 79 0\n\nseed-to-soil map:\n50 98 2\n"
+            .parse()
+            .unwrap();
+        assert_eq!(Ok(0), Fertilizer::part_1(&zero_length));
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 11,
+                token: "0".to_string(),
+                expected: "a range of at least one seed",
+                excerpt: "seeds: 79 0".to_string(),
+            }),
+            Fertilizer::part_2(&zero_length)
+        );
+    }
+
+    #[test]
+    fn huge_seed_ranges() {
+        // far more seeds than could ever be looked at one by one
+        let a: Almanac = "seeds: 1000 1000000000000000
+
+seed-to-soil map:
+5 1000 10
+"
+        .parse()
+        .unwrap();
+        assert_eq!(Ok(5), Fertilizer::part_2(&a));
+        assert_eq!(Ok(5), Fertilizer::part_1(&a));
+    }
+
+    #[test]
+    fn bad_almanacs() {
+        assert_eq!(
+            Err(Error::Parse {
+                line: 4,
+                column: 1,
+                token: "50 98".to_string(),
+                expected: "a destination, a source and a length",
+                excerpt: "50 98".to_string(),
+            }),
+            "seeds: 1 2\n\nseed-to-soil map:\n50 98".parse::<Almanac>()
+        );
+        assert_eq!(
+            true,
+            matches!(
+                "seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n0 99 5".parse::<Almanac>(),
+                Err(Error::Parse { line: 5, .. })
+            )
+        );
+        assert_eq!(
+            true,
+            matches!(
+                "seeds: 1 2\n\nseed-to-soil map:\n\nwater-to-light map:\n".parse::<Almanac>(),
+                Err(Error::Parse {
+                    line: 5,
+                    column: 1,
+                    ..
+                })
+            )
+        );
+        assert_eq!(
+            true,
+            matches!(
+                "seed: 1".parse::<Almanac>(),
+                Err(Error::Parse { line: 1, .. })
+            )
+        );
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 7,
+                token: String::new(),
+                expected: "a seed number",
+                excerpt: "seeds:".to_string(),
+            }),
+            "seeds:".parse::<Almanac>()
+        );
+        // the first line maps numbers onto themselves, which still leaves them taken
+        assert_eq!(
+            true,
+            matches!(
+                "seeds: 1 2\n\nseed-to-soil map:\n5 5 10\n0 7 3".parse::<Almanac>(),
+                Err(Error::Parse { line: 5, .. })
+            )
+        );
+    }
+}
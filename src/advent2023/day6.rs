@@ -0,0 +1,316 @@
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Day 6: Wait For It
+pub struct WaitForIt;
+
+impl Solution for WaitForIt {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Wait For It";
+
+    type Input = Races;
+    type Answer = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.races().iter().map(Race::ways_to_win).product())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        Ok(input.joined().ways_to_win())
+    }
+}
+
+/// A race lasting `time` milliseconds where the boat has to go further than `record` millimeters.
+/// Both can be as large as needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub time: BigUint,
+    pub record: BigUint,
+}
+
+impl Race {
+    pub fn new(time: impl Into<BigUint>, record: impl Into<BigUint>) -> Self {
+        Race {
+            time: time.into(),
+            record: record.into(),
+        }
+    }
+
+    /// How far the boat goes when the button is held for `hold` milliseconds, which is 0 if it is
+    /// held for the whole race or longer.
+    pub fn distance(&self, hold: &BigUint) -> BigUint {
+        if hold < &self.time {
+            hold * (&self.time - hold)
+        } else {
+            BigUint::default()
+        }
+    }
+
+    /// Number of whole milliseconds the button can be held for to beat the record.
+    ///
+    /// Holding for `h` beats the record `d` in a race of `t` when `h * (t - h) > d`, which is the
+    /// same as `(t - 2h)² < t² - 4d`. With `m` the largest whole number whose square is below
+    /// `t² - 4d`, the holds that win are those where `t - 2h` is between `-m` and `m`. Those are the
+    /// numbers in that range with the same parity as `t`, and there are `m + 1` of them when `m` has
+    /// the parity of `t` and `m` otherwise. Only integer square roots are taken, so the count is
+    /// exact however large the numbers are.
+    pub fn ways_to_win(&self) -> BigUint {
+        let square = &self.time * &self.time;
+        let four_records = &self.record * 4u32;
+        if square <= four_records {
+            return BigUint::default();
+        }
+
+        let m = (square - four_records - 1u32).sqrt();
+        if m.bit(0) == self.time.bit(0) {
+            m + 1u32
+        } else {
+            m
+        }
+    }
+}
+
+/// The races of the sheet, read as separate races for part 1 and as a single race with the digits
+/// of every race run together for part 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Races {
+    races: Vec<Race>,
+    joined: Race,
+}
+
+impl Races {
+    pub fn races(&self) -> &[Race] {
+        &self.races
+    }
+
+    /// The single race the sheet describes when the spaces between the numbers are ignored.
+    pub fn joined(&self) -> &Race {
+        &self.joined
+    }
+}
+
+impl FromStr for Races {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let time = lines.next().unwrap_or((0, ""));
+        let (times, joined_time) = read_numbers(time, "\"Time:\"")?;
+        // a missing line of distances is reported on the line after the times
+        let distances = lines.next().unwrap_or((time.0 + 1, ""));
+        let (records, joined_record) = read_numbers(distances, "\"Distance:\"")?;
+        if let Some((index, line)) = lines.next() {
+            return Err(Error::field(index + 1, line, line, "the end of the sheet"));
+        }
+        if times.len() != records.len() {
+            let (index, line) = distances;
+            return Err(Error::field(
+                index + 1,
+                line,
+                line,
+                "a distance for every time",
+            ));
+        }
+
+        Ok(Races {
+            races: times
+                .into_iter()
+                .zip(records)
+                .map(|(time, record)| Race { time, record })
+                .collect(),
+            joined: Race {
+                time: joined_time,
+                record: joined_record,
+            },
+        })
+    }
+}
+
+/// Reads the numbers of a line starting with the label, along with the number all their digits make
+/// together. The label is given in quotes, as errors show it.
+fn read_numbers(
+    (index, line): (usize, &str),
+    label: &'static str,
+) -> Result<(Vec<BigUint>, BigUint)> {
+    let error = |token: &str, expected| Error::field(index + 1, line, token, expected);
+    let Some(numbers) = line.strip_prefix(label.trim_matches('"')) else {
+        return Err(error(line, label));
+    };
+
+    let mut digits = String::new();
+    let numbers = numbers
+        .split_whitespace()
+        .map(|n| {
+            digits.push_str(n);
+            BigUint::parse_bytes(n.as_bytes(), 10)
+                .filter(|_| n.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(|| error(n, "a number"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if numbers.is_empty() {
+        return Err(error(&line[line.len()..], "a number"));
+    }
+
+    let joined = BigUint::parse_bytes(digits.as_bytes(), 10).expect("only digits");
+    Ok((numbers, joined))
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{Race, Races, WaitForIt};
+    use crate::advent2023::Solution;
+    use crate::error::Error;
+
+    const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200";
+
+    fn big(n: &str) -> BigUint {
+        n.parse().unwrap()
+    }
+
+    /// Tries every hold, which is only possible for short races.
+    fn brute_force(race: &Race) -> BigUint {
+        let time: u64 = (&race.time).try_into().unwrap();
+        let wins = (0..=time)
+            .filter(|h| race.distance(&BigUint::from(*h)) > race.record)
+            .count();
+        BigUint::from(wins)
+    }
+
+    /// The usual floating point solution, which loses precision once the numbers get large.
+    fn floating_point(time: u64, record: u64) -> u64 {
+        let (t, d) = (time as f64, record as f64);
+        let root = (t * t - 4.0 * d).sqrt();
+        let low = ((t - root) / 2.0).floor() as u64 + 1;
+        let high = ((t + root) / 2.0).ceil() as u64 - 1;
+        high.saturating_sub(low) + 1
+    }
+
+    #[test]
+    fn read_races() {
+        let races: Races = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            vec![
+                Race::new(7u32, 9u32),
+                Race::new(15u32, 40u32),
+                Race::new(30u32, 200u32)
+            ],
+            races.races()
+        );
+        assert_eq!(&Race::new(71530u32, 940200u32), races.joined());
+    }
+
+    #[test]
+    fn solution() {
+        let races = WaitForIt::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(BigUint::from(288u32)), WaitForIt::part_1(&races));
+        assert_eq!(Ok(BigUint::from(71503u32)), WaitForIt::part_2(&races));
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        for time in 0..40u32 {
+            for record in 0..=(time * time / 4 + 1) {
+                let race = Race::new(time, record);
+                assert_eq!(brute_force(&race), race.ways_to_win(), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn records_that_can_not_be_beaten() {
+        // the best hold is half the race, which goes exactly t²/4
+        assert_eq!(BigUint::default(), Race::new(10u32, 25u32).ways_to_win());
+        assert_eq!(BigUint::from(1u32), Race::new(10u32, 24u32).ways_to_win());
+        assert_eq!(BigUint::default(), Race::new(9u32, 20u32).ways_to_win());
+        assert_eq!(BigUint::from(2u32), Race::new(9u32, 19u32).ways_to_win());
+        assert_eq!(BigUint::default(), Race::new(0u32, 0u32).ways_to_win());
+        assert_eq!(BigUint::from(1u32), Race::new(2u32, 0u32).ways_to_win());
+    }
+
+    #[test]
+    fn beyond_floating_point() {
+        // holding for h goes exactly h * (t - h), so a record one below that is beaten by every hold
+        // from h to t - h and a record equal to it by one less at each end
+        let time: u64 = 3_000_000_000_017;
+        let hold: u64 = 1_000_003;
+        let reached = hold * (time - hold);
+        let all = time - 2 * hold + 1;
+        assert_eq!(
+            BigUint::from(all),
+            Race::new(time, reached - 1).ways_to_win()
+        );
+        assert_eq!(
+            BigUint::from(all - 2),
+            Race::new(time, reached).ways_to_win()
+        );
+        // which is where floating point gets it wrong
+        assert_eq!(all - 2, floating_point(time, reached - 1));
+    }
+
+    #[test]
+    fn beyond_u64() {
+        let time = big("1000000000000000000000000000000");
+        let hold = big("123456789012345678901234567");
+        let reached = &hold * (&time - &hold);
+        let all = &time - &hold * 2u32 + 1u32;
+        assert_eq!(all, Race::new(time.clone(), &reached - 1u32).ways_to_win());
+        assert_eq!(
+            all - 2u32,
+            Race::new(time.clone(), reached.clone()).ways_to_win()
+        );
+        assert_eq!(reached, Race::new(time, 0u32).distance(&hold));
+    }
+
+    #[test]
+    fn joined_race_beyond_u64() {
+        let races: Races = "Time: 99999999999 99999999999\nDistance: 1 0"
+            .parse()
+            .unwrap();
+        let time = big("9999999999999999999999");
+        assert_eq!(&Race::new(time.clone(), 10u32), races.joined());
+        assert_eq!(
+            // every hold but none at all and the whole race goes further than 10
+            Ok(&time - 1u32),
+            WaitForIt::part_2(&races)
+        );
+    }
+
+    #[test]
+    fn bad_sheets() {
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 15,
+                token: "4x".to_string(),
+                expected: "a number",
+                excerpt: "Distance:  9  4x".to_string(),
+            }),
+            "Time: 7 15\nDistance:  9  4x".parse::<Races>()
+        );
+        assert_eq!(true, "Time: 7 15\nDistance: 9".parse::<Races>().is_err());
+        assert_eq!(
+            Err(Error::Parse {
+                line: 3,
+                column: 1,
+                token: String::new(),
+                expected: "\"Distance:\"",
+                excerpt: String::new(),
+            }),
+            "\nTime: 7 15\n\n".parse::<Races>()
+        );
+        assert_eq!(true, "Time: 7\n".parse::<Races>().is_err());
+        assert_eq!(true, "Time:\nDistance:".parse::<Races>().is_err());
+        assert_eq!(true, "Time: +7\nDistance: 9".parse::<Races>().is_err());
+    }
+}
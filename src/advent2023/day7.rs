@@ -0,0 +1,491 @@
+use std::str::FromStr;
+
+use super::Solution;
+use crate::error::{Error, Result};
+
+/// Day 7: Camel Cards
+pub struct CamelCards;
+
+impl Solution for CamelCards {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Camel Cards";
+
+    type Input = Plays;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Self::Answer> {
+        input.winnings(&Rules::standard())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Self::Answer> {
+        input.winnings(&Rules::jokers_wild())
+    }
+}
+
+/// Type of a hand, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::HighCard,
+        Category::OnePair,
+        Category::TwoPair,
+        Category::ThreeOfAKind,
+        Category::FullHouse,
+        Category::FourOfAKind,
+        Category::FiveOfAKind,
+    ];
+}
+
+/// Cards of the standard game, from the weakest to the strongest.
+const STANDARD_ORDER: &str = "23456789TJQKA";
+
+/// How hands are ranked: the cards from the weakest to the strongest, and optionally a joker that
+/// stands in for whichever card makes the strongest hand while being weaker than every other card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    order: Vec<char>,
+    joker: Option<char>,
+}
+
+impl Rules {
+    /// The rules of part 1.
+    pub fn standard() -> Self {
+        Rules::custom(STANDARD_ORDER, None).expect("valid order")
+    }
+
+    /// The rules of part 2, where `J` is a joker and the weakest card.
+    pub fn jokers_wild() -> Self {
+        Rules::custom("J23456789TQKA", Some('J')).expect("valid order")
+    }
+
+    /// Rules with the cards of `order` from the weakest to the strongest. None when a card shows up
+    /// twice or the joker is not one of the cards.
+    pub fn custom(order: &str, joker: Option<char>) -> Option<Self> {
+        let order: Vec<char> = order.chars().collect();
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != order.len() || joker.is_some_and(|j| !order.contains(&j)) {
+            return None;
+        }
+        Some(Rules { order, joker })
+    }
+
+    /// How strong a card is on its own, from 0 for the weakest. None when it is not one of the
+    /// cards.
+    pub fn strength(&self, card: char) -> Option<u8> {
+        self.order.iter().position(|c| *c == card).map(|i| i as u8)
+    }
+
+    /// The type of the hand, with every joker counted as the card that makes it strongest. That is
+    /// always the card the hand already has most of, as adding to the largest group beats starting
+    /// or growing any other.
+    pub fn category(&self, cards: &[char; 5]) -> Category {
+        let mut counts: Vec<(char, u8)> = Vec::new();
+        let mut jokers = 0;
+        for card in cards {
+            if Some(*card) == self.joker {
+                jokers += 1;
+                continue;
+            }
+            match counts.iter_mut().find(|(c, _)| c == card) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*card, 1)),
+            }
+        }
+
+        let mut sizes: Vec<u8> = counts.into_iter().map(|(_, count)| count).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        match sizes.first_mut() {
+            Some(largest) => *largest += jokers,
+            None => sizes.push(jokers),
+        }
+
+        match sizes[..] {
+            [5] => Category::FiveOfAKind,
+            [4, 1] => Category::FourOfAKind,
+            [3, 2] => Category::FullHouse,
+            [3, 1, 1] => Category::ThreeOfAKind,
+            [2, 2, 1] => Category::TwoPair,
+            [2, 1, 1, 1] => Category::OnePair,
+            _ => Category::HighCard,
+        }
+    }
+
+    /// The hand of five cards ranked under these rules, or None when it is not five of the cards.
+    pub fn hand(&self, cards: &str) -> Option<Hand> {
+        let cards: [char; 5] = cards.chars().collect::<Vec<_>>().try_into().ok()?;
+        let mut strengths = [0; 5];
+        for (strength, card) in strengths.iter_mut().zip(cards) {
+            *strength = self.strength(card)?;
+        }
+        Some(Hand {
+            category: self.category(&cards),
+            strengths,
+            cards,
+        })
+    }
+}
+
+/// Five cards ranked under some [Rules]. Hands order by their category first and then card by card
+/// from the first, so only hands ranked under the same rules should be compared.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hand {
+    // the order of the fields is the order hands are compared in
+    category: Category,
+    strengths: [u8; 5],
+    cards: [char; 5],
+}
+
+impl Hand {
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    pub fn cards(&self) -> String {
+        self.cards.iter().collect()
+    }
+}
+
+/// A hand of cards as written in the puzzle input along with what was bid on it. Plays compare by
+/// their cards and bid, wherever they were read from.
+#[derive(Debug, Clone, Eq)]
+pub struct Play {
+    pub cards: String,
+    pub bid: u64,
+    /// Where the cards were read from, as they can only be checked once the rules are known.
+    line_number: usize,
+    column: usize,
+}
+
+impl PartialEq for Play {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.cards, self.bid) == (&other.cards, other.bid)
+    }
+}
+
+/// Every hand of the puzzle input with its bid, in the order of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plays {
+    plays: Vec<Play>,
+}
+
+impl Plays {
+    pub fn new(plays: Vec<Play>) -> Self {
+        Plays { plays }
+    }
+
+    pub fn plays(&self) -> &[Play] {
+        &self.plays
+    }
+
+    /// The hands ranked from the weakest to the strongest under the rules, with the plays they
+    /// were dealt in.
+    pub fn ranked(&self, rules: &Rules) -> Result<Vec<(Hand, &Play)>> {
+        let mut ranked = self
+            .plays
+            .iter()
+            .map(|play| match rules.hand(&play.cards) {
+                Some(hand) => Ok((hand, play)),
+                None => Err(Error::Parse {
+                    line: play.line_number,
+                    column: play.column,
+                    token: play.cards.clone(),
+                    expected: "five cards of the rules",
+                    excerpt: String::new(),
+                }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ranked.sort_by(|(a, x), (b, y)| (a, x.bid).cmp(&(b, y.bid)));
+        Ok(ranked)
+    }
+
+    /// Every bid multiplied by the rank of its hand, where the weakest hand has rank 1.
+    pub fn winnings(&self, rules: &Rules) -> Result<u64> {
+        self.ranked(rules)?
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |sum, (index, (_, play))| {
+                (index as u64 + 1)
+                    .checked_mul(play.bid)
+                    .and_then(|winning| sum.checked_add(winning))
+                    .ok_or(Error::Overflow {
+                        line: play.line_number,
+                    })
+            })
+    }
+}
+
+impl FromStr for Plays {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let plays = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| read_play(index + 1, line))
+            .collect::<Result<_>>()?;
+        Ok(Plays::new(plays))
+    }
+}
+
+/// Reads a line such as "32T3K 765" holding five cards and a bid. Any symbol can be a card, as only
+/// the [Rules] a hand is ranked under say which cards there are.
+pub fn read_play(line_number: usize, line: &str) -> Result<Play> {
+    let error = |token: &str, expected| Error::field(line_number, line, token, expected);
+    let [cards, bid] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+        return Err(error(line, "five cards and a bid"));
+    };
+    if cards.chars().count() != 5 {
+        return Err(error(cards, "five cards such as \"32T3K\""));
+    }
+    Ok(Play {
+        cards: cards.to_string(),
+        bid: bid.parse().map_err(|_| error(bid, "a bid"))?,
+        line_number,
+        // the cards are the first thing on the line
+        column: line.chars().take_while(|c| c.is_whitespace()).count() + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{read_play, CamelCards, Category, Hand, Play, Plays, Rules, STANDARD_ORDER};
+    use crate::advent2023::Solution;
+    use crate::error::Error;
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    fn hand(rules: &Rules, cards: &str) -> Hand {
+        rules.hand(cards).unwrap()
+    }
+
+    /// Every hand of five cards there is with the standard cards, which is 13⁵ of them.
+    fn every_hand() -> impl Iterator<Item = [char; 5]> {
+        let cards: Vec<char> = STANDARD_ORDER.chars().collect();
+        (0..13usize.pow(5)).map(move |mut n| {
+            let mut hand = ['2'; 5];
+            for card in hand.iter_mut() {
+                *card = cards[n % 13];
+                n /= 13;
+            }
+            hand
+        })
+    }
+
+    /// The category straight from the definitions of the puzzle, with no jokers.
+    fn category_by_definition(cards: &[char; 5]) -> Category {
+        let mut counts = BTreeMap::new();
+        for card in cards {
+            *counts.entry(card).or_insert(0) += 1;
+        }
+        let mut sizes: Vec<i32> = counts.into_values().collect();
+        sizes.sort();
+        match sizes[..] {
+            [5] => Category::FiveOfAKind,
+            [1, 4] => Category::FourOfAKind,
+            [2, 3] => Category::FullHouse,
+            [1, 1, 3] => Category::ThreeOfAKind,
+            [1, 2, 2] => Category::TwoPair,
+            [1, 1, 1, 2] => Category::OnePair,
+            _ => Category::HighCard,
+        }
+    }
+
+    #[test]
+    fn every_category_of_every_hand() {
+        let rules = Rules::standard();
+        let mut found: BTreeMap<Category, usize> = BTreeMap::new();
+        for cards in every_hand() {
+            let category = rules.category(&cards);
+            assert_eq!(category_by_definition(&cards), category, "{cards:?}");
+            *found.entry(category).or_default() += 1;
+        }
+
+        // ways to deal each category in order: pick the ranks of the groups, then their places
+        assert_eq!(
+            vec![
+                (Category::HighCard, 13 * 12 * 11 * 10 * 9),
+                (Category::OnePair, 13 * 220 * 60),
+                (Category::TwoPair, 78 * 11 * 30),
+                (Category::ThreeOfAKind, 13 * 66 * 20),
+                (Category::FullHouse, 13 * 12 * 10),
+                (Category::FourOfAKind, 13 * 12 * 5),
+                (Category::FiveOfAKind, 13),
+            ],
+            found.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn jokers_make_the_best_hand() {
+        let rules = Rules::jokers_wild();
+        for cards in every_hand() {
+            // every way of turning the jokers into cards the hand has, or into aces when it is all
+            // jokers, as any other card can only make the hand weaker
+            let mut options: Vec<char> = cards.iter().copied().filter(|c| *c != 'J').collect();
+            options.push('A');
+            let jokers: Vec<_> = (0..5).filter(|i| cards[*i] == 'J').collect();
+            let best = (0..options.len().pow(jokers.len() as u32))
+                .map(|mut n| {
+                    let mut replaced = cards;
+                    for i in &jokers {
+                        replaced[*i] = options[n % options.len()];
+                        n /= options.len();
+                    }
+                    category_by_definition(&replaced)
+                })
+                .max()
+                .unwrap();
+            assert_eq!(best, rules.category(&cards), "{cards:?}");
+        }
+    }
+
+    #[test]
+    fn categories() {
+        let rules = Rules::standard();
+        let categories: Vec<_> = [
+            "23456", "A23A4", "23432", "TTT98", "23332", "AA8AA", "AAAAA",
+        ]
+        .iter()
+        .map(|cards| hand(&rules, cards).category())
+        .collect();
+        assert_eq!(Category::ALL.to_vec(), categories);
+    }
+
+    #[test]
+    fn order_of_hands() {
+        let rules = Rules::standard();
+        assert_eq!(true, hand(&rules, "33332") > hand(&rules, "2AAAA"));
+        assert_eq!(true, hand(&rules, "77888") > hand(&rules, "77788"));
+        assert_eq!(true, hand(&rules, "KK677") > hand(&rules, "KTJJT"));
+        assert_eq!(true, hand(&rules, "22345") > hand(&rules, "AKQJT"));
+        let jokers = Rules::jokers_wild();
+        assert_eq!(true, hand(&jokers, "JKKK2") < hand(&jokers, "QQQQ2"));
+        assert_eq!(true, hand(&jokers, "KTJJT") > hand(&jokers, "QQQJA"));
+        assert_eq!(Category::FiveOfAKind, hand(&jokers, "JJJJJ").category());
+        assert_eq!(true, hand(&jokers, "JJJJJ") < hand(&jokers, "22222"));
+    }
+
+    #[test]
+    fn custom_rules() {
+        // aces low and the two as a joker
+        let rules = Rules::custom("2A3456789TJQK", Some('2')).unwrap();
+        assert_eq!(Some(1), rules.strength('A'));
+        assert_eq!(Category::FourOfAKind, hand(&rules, "KK2K3").category());
+        assert_eq!(true, hand(&rules, "A3456") < hand(&rules, "3A456"));
+        assert_eq!(None, rules.hand("AAAAX"));
+        assert_eq!(None, rules.hand("AAAA"));
+        assert_eq!(None, Rules::custom("AKQA", None));
+        assert_eq!(None, Rules::custom("AKQ", Some('J')));
+
+        // three kings either way in the standard game, but four with the joker
+        let p: Plays = "KK2K3 1\nKKQK3 2".parse().unwrap();
+        assert_eq!(Ok(1 + 2 * 2), p.winnings(&Rules::standard()));
+        assert_eq!(Ok(2 + 2), p.winnings(&rules));
+        let short = Rules::custom("AKQ", None).unwrap();
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 1,
+                token: "KK2K3".to_string(),
+                expected: "five cards of the rules",
+                excerpt: String::new(),
+            }),
+            p.winnings(&short)
+        );
+
+        // cards outside the standard game are read and left to the rules, which know the line
+        let p: Plays = "\nXXYZZ 1\n\n  ZZZZA 2".parse().unwrap();
+        let xyza = Rules::custom("XYZA", None).unwrap();
+        assert_eq!(Ok(1 + 2 * 2), p.winnings(&xyza));
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 1,
+                token: "XXYZZ".to_string(),
+                expected: "five cards of the rules",
+                excerpt: String::new(),
+            }),
+            p.winnings(&Rules::standard())
+        );
+        assert_eq!(
+            Err(Error::Parse {
+                line: 4,
+                column: 3,
+                token: "ZZZZA".to_string(),
+                expected: "five cards of the rules",
+                excerpt: String::new(),
+            }),
+            p.winnings(&Rules::custom("XYZ", None).unwrap())
+        );
+
+        // the winnings of the strongest hand overflow, which is reported on its line
+        let p: Plays = "\n23456 1\n\nAAAAA 18446744073709551615".parse().unwrap();
+        assert_eq!(
+            Err(Error::Overflow { line: 4 }),
+            p.winnings(&Rules::standard())
+        );
+    }
+
+    #[test]
+    fn solution() {
+        let p = CamelCards::parse(EXAMPLE).unwrap();
+        assert_eq!(Ok(6440), CamelCards::part_1(&p));
+        assert_eq!(Ok(5905), CamelCards::part_2(&p));
+        let ranked: Vec<_> = p
+            .ranked(&Rules::standard())
+            .unwrap()
+            .into_iter()
+            .map(|(hand, _)| hand.cards())
+            .collect();
+        assert_eq!(vec!["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"], ranked);
+    }
+
+    #[test]
+    fn read_plays() {
+        assert_eq!(
+            Ok(Play {
+                cards: "T55J5".to_string(),
+                bid: 684,
+                line_number: 1,
+                column: 1,
+            }),
+            read_play(1, "T55J5 684")
+        );
+        assert_eq!(
+            Err(Error::Parse {
+                line: 3,
+                column: 1,
+                token: "T55J".to_string(),
+                expected: "five cards such as \"32T3K\"",
+                excerpt: "T55J 684".to_string(),
+            }),
+            read_play(3, "T55J 684")
+        );
+        assert_eq!(true, read_play(1, "T55J5").is_err());
+        assert_eq!(true, read_play(1, "T55J5 x").is_err());
+        assert_eq!(true, read_play(1, "T55J5 1 2").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut, Range};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Position of a cell, counted from the top left corner of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Pos { row, col }
+    }
+
+    /// The position moved by the given number of rows and columns, or None if that would go above
+    /// or left of the grid.
+    pub fn offset(self, rows: isize, cols: isize) -> Option<Pos> {
+        Some(Pos {
+            row: self.row.checked_add_signed(rows)?,
+            col: self.col.checked_add_signed(cols)?,
+        })
+    }
+}
+
+/// Offsets of the cells sharing a side with a cell, clockwise from the top.
+const SIDES: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+/// Offsets of the cells sharing a side or a corner with a cell, clockwise from the top left.
+const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// A rectangle of cells, such as a run of cells in a single row or column.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub rows: Range<usize>,
+    pub cols: Range<usize>,
+}
+
+impl Span {
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.rows.contains(&pos.row) && self.cols.contains(&pos.col)
+    }
+
+    /// Every position in the span, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.rows
+            .clone()
+            .flat_map(|row| self.cols.clone().map(move |col| Pos { row, col }))
+    }
+}
+
+/// A rectangular grid of cells stored row by row, as drawn by many of the puzzles. Every access is
+/// bounds checked, so positions outside the grid give None rather than wrapping to another row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of the given width filled row by row from the cells, or None if the cells do not fill
+    /// a whole number of rows.
+    pub fn new(width: usize, cells: Vec<T>) -> Option<Self> {
+        let height = match width {
+            0 if cells.is_empty() => 0,
+            0 => return None,
+            _ if !cells.len().is_multiple_of(width) => return None,
+            _ => cells.len() / width,
+        };
+        Some(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Reads a grid with a line of text per row, turning every character into a cell with `cell`.
+    /// Lines end with "\n" or "\r\n" and must all be as long as the first, though empty lines at the
+    /// end are ignored. Fails on the first character `cell` gives None for, with `expected`
+    /// describing the characters it takes.
+    pub fn parse_with(
+        text: &str,
+        expected: &'static str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        let text = text.trim_end_matches(['\r', '\n']);
+        for (index, line) in text.lines().enumerate() {
+            let mut count = 0;
+            for (column, c) in line.chars().enumerate() {
+                let Some(value) = cell(c) else {
+                    return Err(Error::Parse {
+                        line: index + 1,
+                        column: column + 1,
+                        token: c.to_string(),
+                        expected,
+                        excerpt: line.to_string(),
+                    });
+                };
+                cells.push(value);
+                count += 1;
+            }
+
+            if *width.get_or_insert(count) != count {
+                return Err(Error::Parse {
+                    line: index + 1,
+                    column: 1,
+                    token: line.to_string(),
+                    expected: "a row as wide as the first",
+                    excerpt: line.to_string(),
+                });
+            }
+        }
+
+        Ok(Grid::new(width.unwrap_or(0), cells).expect("every row has the same width"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.in_bounds(pos)
+            .then(|| &self.cells[pos.row * self.width + pos.col])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.row * self.width + pos.col])
+        } else {
+            None
+        }
+    }
+
+    /// The cells of a row from left to right. Panics if the row is outside the grid.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.height, "row {row} is outside the grid");
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// The cells of a column from top to bottom. Panics if the column is outside the grid.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {col} is outside the grid");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos { row, col }))
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The cells above, right, below and left of the position that are in the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &SIDES)
+    }
+
+    /// The cells sharing a side or a corner with the position that are in the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &AROUND)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(rows, cols)| pos.offset(*rows, *cols))
+            .filter(|p| self.in_bounds(*p))
+    }
+
+    /// The cells in the grid that share a side or a corner with the span without being part of it,
+    /// row by row.
+    pub fn around(&self, span: &Span) -> impl Iterator<Item = Pos> {
+        let rows = span.rows.start.saturating_sub(1)..(span.rows.end + 1).min(self.height);
+        let cols = span.cols.start.saturating_sub(1)..(span.cols.end + 1).min(self.width);
+        let span = span.clone();
+        rows.flat_map(move |row| cols.clone().map(move |col| Pos { row, col }))
+            .filter(move |p| !span.contains(*p))
+    }
+
+    /// The longest runs of cells within each row that `keep` holds for, top to bottom and left to
+    /// right.
+    pub fn row_spans(&self, mut keep: impl FnMut(&T) -> bool) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for row in 0..self.height {
+            for cols in runs(self.row(row).iter().map(&mut keep)) {
+                spans.push(Span {
+                    rows: row..row + 1,
+                    cols,
+                });
+            }
+        }
+        spans
+    }
+
+    /// The longest runs of cells within each column that `keep` holds for, left to right and top to
+    /// bottom.
+    pub fn column_spans(&self, mut keep: impl FnMut(&T) -> bool) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for col in 0..self.width {
+            for rows in runs(self.column(col).map(&mut keep)) {
+                spans.push(Span {
+                    rows,
+                    cols: col..col + 1,
+                });
+            }
+        }
+        spans
+    }
+}
+
+/// The ranges of consecutive trues.
+fn runs(kept: impl Iterator<Item = bool>) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    for (index, kept) in kept.enumerate() {
+        match (kept, start) {
+            (true, None) => start = Some(index),
+            (false, Some(s)) => {
+                runs.push(s..index);
+                start = None;
+            }
+            _ => {}
+        }
+        end = index + 1;
+    }
+    if let Some(s) = start {
+        runs.push(s..end);
+    }
+    runs
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the position is outside the grid, use [Grid::get] when it may be.
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+/// A grid of characters taking any text with lines of the same length.
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Grid::parse_with(s, "a character", Some)
+    }
+}
+
+/// The cells row by row, with a line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            for cell in self.row(row) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Pos, Span};
+    use crate::error::Error;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef\r\nghi\n".parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let g = grid();
+        assert_eq!((3, 3), (g.width(), g.height()));
+        assert_eq!('f', g[Pos::new(1, 2)]);
+        assert_eq!(Some(&'g'), g.get(Pos::new(2, 0)));
+        assert_eq!(None, g.get(Pos::new(1, 3)));
+        assert_eq!(None, g.get(Pos::new(3, 0)));
+        assert_eq!("abc\ndef\nghi\n", g.to_string());
+    }
+
+    #[test]
+    fn trailing_blank_lines() {
+        assert_eq!(Ok(grid()), "abc\ndef\nghi\n\n\r\n".parse());
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 1,
+                token: "".to_string(),
+                expected: "a row as wide as the first",
+                excerpt: "".to_string(),
+            }),
+            "abc\n\nghi\n".parse::<Grid<char>>()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 1,
+                token: "de".to_string(),
+                expected: "a row as wide as the first",
+                excerpt: "de".to_string(),
+            }),
+            "abc\nde".parse::<Grid<char>>()
+        );
+        assert_eq!(
+            Err(Error::Parse {
+                line: 1,
+                column: 2,
+                token: "x".to_string(),
+                expected: "a digit",
+                excerpt: "1x".to_string(),
+            }),
+            Grid::parse_with("1x", "a digit", |c| c.to_digit(10))
+        );
+        let empty: Grid<char> = "".parse().unwrap();
+        assert_eq!((0, 0), (empty.width(), empty.height()));
+    }
+
+    #[test]
+    fn new_needs_whole_rows() {
+        assert_eq!(2, Grid::new(2, vec![1, 2, 3, 4]).unwrap().height());
+        assert_eq!(None, Grid::new(3, vec![1, 2, 3, 4]));
+        assert_eq!(None, Grid::new(0, vec![1]));
+    }
+
+    #[test]
+    fn neighbours() {
+        let g = grid();
+        let chars = |positions: Vec<Pos>| positions.into_iter().map(|p| g[p]).collect::<String>();
+        assert_eq!("bfhd", chars(g.neighbours4(Pos::new(1, 1)).collect()));
+        assert_eq!("abcfihgd", chars(g.neighbours8(Pos::new(1, 1)).collect()));
+        assert_eq!("bd", chars(g.neighbours4(Pos::new(0, 0)).collect()));
+        assert_eq!("efh", chars(g.neighbours8(Pos::new(2, 2)).collect()));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut g = grid();
+        assert_eq!(&['d', 'e', 'f'], g.row(1));
+        assert_eq!("cfi", g.column(2).collect::<String>());
+        g[Pos::new(0, 2)] = 'z';
+        assert_eq!("zfi", g.column(2).collect::<String>());
+        assert_eq!(None, g.get_mut(Pos::new(0, 3)));
+    }
+
+    #[test]
+    fn spans() {
+        let g: Grid<char> = "12.3\n4..5\n..67".parse().unwrap();
+        let digits = g.row_spans(char::is_ascii_digit);
+        assert_eq!(
+            vec![
+                Span {
+                    rows: 0..1,
+                    cols: 0..2
+                },
+                Span {
+                    rows: 0..1,
+                    cols: 3..4
+                },
+                Span {
+                    rows: 1..2,
+                    cols: 0..1
+                },
+                Span {
+                    rows: 1..2,
+                    cols: 3..4
+                },
+                Span {
+                    rows: 2..3,
+                    cols: 2..4
+                },
+            ],
+            digits
+        );
+        let columns = g.column_spans(char::is_ascii_digit);
+        assert_eq!(
+            Span {
+                rows: 0..3,
+                cols: 3..4
+            },
+            columns[3]
+        );
+        assert_eq!(4, columns.len());
+    }
+
+    #[test]
+    fn around_span() {
+        let g: Grid<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        let around = |span: Span| g.around(&span).map(|p| g[p]).collect::<String>();
+        let middle = Span {
+            rows: 1..2,
+            cols: 1..3,
+        };
+        assert_eq!("abcdehijkl", around(middle.clone()));
+        assert_eq!("fg", middle.positions().map(|p| g[p]).collect::<String>());
+        assert_eq!(
+            "cgh",
+            around(Span {
+                rows: 0..1,
+                cols: 3..4
+            })
+        );
+    }
+}
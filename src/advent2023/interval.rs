@@ -0,0 +1,478 @@
+use std::fmt::{Display, Formatter};
+
+/// The numbers from `start` up to but not including `end`. Numbers go up to `u64::MAX - 1`, as
+/// `u64::MAX` itself is the end of the last interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Interval {
+    /// Every number there is.
+    pub const ALL: Interval = Interval {
+        start: 0,
+        end: u64::MAX,
+    };
+
+    pub fn new(start: u64, end: u64) -> Self {
+        Interval { start, end }
+    }
+
+    /// The interval of `len` numbers from `start`, or None when it would go past the last number.
+    pub fn with_len(start: u64, len: u64) -> Option<Self> {
+        Some(Interval {
+            start,
+            end: start.checked_add(len)?,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The numbers in both intervals, or None when they have none in common.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let common = Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        };
+        (!common.is_empty()).then_some(common)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of numbers stored as the intervals it is made of, so that huge sets take little space.
+/// The intervals are kept sorted, and ones that overlap or touch are merged, so two sets holding the
+/// same numbers always compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// The sorted, disjoint intervals making up the set.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Number of numbers in the set.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        // the first interval ending after the value is the only one that can hold it
+        let index = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(index).is_some_and(|i| i.contains(value))
+    }
+
+    /// Adds every number of the interval to the set.
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        // intervals before `first` end before the new one starts and intervals from `last` on start
+        // after it ends, everything in between is merged into it
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| Interval {
+                start: merged.start.min(i.start),
+                end: merged.end.max(i.end),
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        union.extend(other.intervals.iter().copied());
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut common = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while let (Some(x), Some(y)) = (self.intervals.get(a), other.intervals.get(b)) {
+            common.extend(x.intersection(y));
+            if x.end <= y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { intervals: common }
+    }
+
+    /// The numbers of this set that are not in the other.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        self.intersection(&other.complement())
+    }
+
+    /// Every number that is not in the set.
+    pub fn complement(&self) -> IntervalSet {
+        let mut gaps = Vec::new();
+        let mut start = 0;
+        for i in &self.intervals {
+            if start < i.start {
+                gaps.push(Interval::new(start, i.start));
+            }
+            start = i.end;
+        }
+        if start < u64::MAX {
+            gaps.push(Interval::new(start, u64::MAX));
+        }
+        IntervalSet { intervals: gaps }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+/// Part of an [OffsetMap]: the numbers of `source` are shifted so that `source.start` becomes
+/// `dest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub source: Interval,
+    pub dest: u64,
+}
+
+impl Piece {
+    fn map(&self, value: u64) -> u64 {
+        self.dest + (value - self.source.start)
+    }
+
+    /// The piece cut down to the numbers of `source` in the interval.
+    fn restrict(&self, interval: &Interval) -> Option<Piece> {
+        let source = self.source.intersection(interval)?;
+        Some(Piece {
+            source,
+            dest: self.map(source.start),
+        })
+    }
+
+    /// Where the numbers of the piece end up.
+    fn image(&self) -> Interval {
+        Interval::new(self.dest, self.dest + self.source.len())
+    }
+}
+
+/// A function from numbers to numbers that shifts whole intervals at once, leaving numbers outside
+/// of its pieces where they are. Sets are mapped an interval at a time and two maps can be composed
+/// into one, so chains of maps can be applied to huge sets without looking at a single number.
+/// Maps compare equal when they move every number to the same place.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct OffsetMap {
+    /// Sorted and disjoint, with no piece mapping numbers onto themselves and no two touching pieces
+    /// that could be one.
+    pieces: Vec<Piece>,
+    /// Every number given a place, including the ones mapped onto themselves that have no piece.
+    mapped: IntervalSet,
+}
+
+impl PartialEq for OffsetMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.pieces == other.pieces
+    }
+}
+
+impl OffsetMap {
+    /// The map leaving every number where it is.
+    pub fn identity() -> Self {
+        OffsetMap::default()
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// Maps the `len` numbers from `source` to the numbers from `dest`. Returns false without
+    /// changing the map when those numbers are already mapped or would be mapped past the last
+    /// number.
+    pub fn insert(&mut self, dest: u64, source: u64, len: u64) -> bool {
+        let (Some(source), Some(_)) = (
+            Interval::with_len(source, len),
+            Interval::with_len(dest, len),
+        ) else {
+            return false;
+        };
+        if source.is_empty() {
+            return true;
+        }
+        let mapped = self.mapped.intervals();
+        let index = mapped.partition_point(|i| i.end <= source.start);
+        if mapped.get(index).is_some_and(|i| i.start < source.end) {
+            return false;
+        }
+        self.mapped.insert(source);
+        let index = self
+            .pieces
+            .partition_point(|p| p.source.end <= source.start);
+        self.pieces.insert(index, Piece { source, dest });
+        self.normalize();
+        true
+    }
+
+    /// Drops pieces mapping numbers onto themselves and joins touching pieces with the same shift.
+    fn normalize(&mut self) {
+        let mut pieces: Vec<Piece> = Vec::with_capacity(self.pieces.len());
+        for piece in self.pieces.drain(..) {
+            if piece.dest == piece.source.start {
+                continue;
+            }
+            match pieces.last_mut() {
+                Some(last)
+                    if last.source.end == piece.source.start && last.image().end == piece.dest =>
+                {
+                    last.source.end = piece.source.end
+                }
+                _ => pieces.push(piece),
+            }
+        }
+        self.pieces = pieces;
+    }
+
+    pub fn map(&self, value: u64) -> u64 {
+        let index = self.pieces.partition_point(|p| p.source.end <= value);
+        match self.pieces.get(index) {
+            Some(piece) if piece.source.contains(value) => piece.map(value),
+            _ => value,
+        }
+    }
+
+    /// The interval cut at the edges of the pieces, with how each part is mapped. Parts between
+    /// pieces map onto themselves.
+    fn split(&self, interval: &Interval) -> Vec<Piece> {
+        let mut parts = Vec::new();
+        let mut start = interval.start;
+        let first = self
+            .pieces
+            .partition_point(|p| p.source.end <= interval.start);
+        for piece in &self.pieces[first..] {
+            if piece.source.start >= interval.end {
+                break;
+            }
+            if start < piece.source.start {
+                parts.push(Piece {
+                    source: Interval::new(start, piece.source.start),
+                    dest: start,
+                });
+            }
+            parts.extend(piece.restrict(interval));
+            start = piece.source.end;
+        }
+        if start < interval.end {
+            parts.push(Piece {
+                source: Interval::new(start, interval.end),
+                dest: start,
+            });
+        }
+        parts
+    }
+
+    /// Where every number of the interval ends up.
+    pub fn map_interval(&self, interval: &Interval) -> IntervalSet {
+        self.split(interval).iter().map(Piece::image).collect()
+    }
+
+    /// Where every number of the set ends up.
+    pub fn map_set(&self, set: &IntervalSet) -> IntervalSet {
+        set.intervals()
+            .iter()
+            .flat_map(|i| self.split(i))
+            .map(|p| p.image())
+            .collect()
+    }
+
+    /// The map applying this map and then `then`, so that
+    /// `a.then(&b).map(x) == b.map(a.map(x))` for every number.
+    pub fn then(&self, then: &OffsetMap) -> OffsetMap {
+        let mut pieces = Vec::new();
+        for part in self.split(&Interval::ALL) {
+            for next in then.split(&part.image()) {
+                // the numbers of `part` that end up in `next`
+                let start = part.source.start + (next.source.start - part.dest);
+                pieces.push(Piece {
+                    source: Interval::with_len(start, next.source.len()).expect("within part"),
+                    dest: next.dest,
+                });
+            }
+        }
+
+        let mut composed = OffsetMap {
+            pieces,
+            mapped: IntervalSet::new(),
+        };
+        composed.normalize();
+        composed.mapped = composed.pieces.iter().map(|p| p.source).collect();
+        composed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, IntervalSet, OffsetMap};
+
+    fn set(intervals: &[(u64, u64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn interval_basics() {
+        let i = Interval::new(3, 7);
+        assert_eq!(4, i.len());
+        assert_eq!(true, i.contains(3));
+        assert_eq!(false, i.contains(7));
+        assert_eq!(
+            Some(Interval::new(5, 7)),
+            i.intersection(&Interval::new(5, 9))
+        );
+        assert_eq!(None, i.intersection(&Interval::new(7, 9)));
+        assert_eq!(None, Interval::with_len(u64::MAX - 1, 2));
+        assert_eq!("3..7", i.to_string());
+    }
+
+    #[test]
+    fn insert_merges() {
+        let mut s = set(&[(10, 20), (30, 40), (50, 60)]);
+        s.insert(Interval::new(20, 30));
+        assert_eq!(set(&[(10, 40), (50, 60)]), s);
+        s.insert(Interval::new(0, 5));
+        s.insert(Interval::new(45, 55));
+        s.insert(Interval::new(8, 8));
+        assert_eq!(vec![(0, 5), (10, 40), (45, 60)], pairs(&s));
+        assert_eq!(5 + 30 + 15, s.len());
+        assert_eq!(Some(0), s.min());
+        assert_eq!(true, s.contains(39));
+        assert_eq!(false, s.contains(40));
+        assert_eq!(false, s.contains(7));
+    }
+
+    fn pairs(s: &IntervalSet) -> Vec<(u64, u64)> {
+        s.intervals().iter().map(|i| (i.start, i.end)).collect()
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(set(&[(0, 40)]), a.union(&b));
+        assert_eq!(set(&[(5, 10), (20, 25), (28, 30)]), a.intersection(&b));
+        assert_eq!(set(&[(0, 5), (25, 28)]), a.difference(&b));
+        assert_eq!(
+            set(&[(10, 20), (30, u64::MAX)]),
+            set(&[(0, 10), (20, 30)]).complement()
+        );
+        assert_eq!(set(&[(0, u64::MAX)]), IntervalSet::new().complement());
+    }
+
+    #[test]
+    fn map_values() {
+        let mut m = OffsetMap::identity();
+        assert_eq!(true, m.insert(50, 98, 2));
+        assert_eq!(true, m.insert(52, 50, 48));
+        assert_eq!(false, m.insert(0, 90, 10));
+        assert_eq!(false, m.insert(u64::MAX - 1, 0, 2));
+        let mapped: Vec<_> = [0, 49, 50, 79, 97, 98, 99, 100].map(|v| m.map(v)).to_vec();
+        assert_eq!(vec![0, 49, 52, 81, 99, 50, 51, 100], mapped);
+    }
+
+    #[test]
+    fn identity_pieces_are_dropped_and_touching_pieces_joined() {
+        let mut m = OffsetMap::identity();
+        assert_eq!(true, m.insert(25, 25, 10));
+        assert_eq!(OffsetMap::identity(), m);
+        // the identity piece is gone but its numbers are still taken
+        assert_eq!(false, m.insert(0, 27, 3));
+        assert_eq!(true, m.insert(100, 0, 10));
+        assert_eq!(true, m.insert(110, 10, 10));
+        assert_eq!(1, m.pieces().len());
+        assert_eq!(Interval::new(0, 20), m.pieces()[0].source);
+    }
+
+    #[test]
+    fn map_whole_sets() {
+        let mut m = OffsetMap::identity();
+        m.insert(50, 98, 2);
+        m.insert(52, 50, 48);
+        // 79..93 lies within one piece, 95..102 crosses both pieces into unmapped numbers
+        assert_eq!(set(&[(81, 95)]), m.map_interval(&Interval::new(79, 93)));
+        assert_eq!(set(&[(50, 52), (97, 102)]), m.map_set(&set(&[(95, 102)])));
+    }
+
+    #[test]
+    fn composition_matches_mapping_twice() {
+        let mut a = OffsetMap::identity();
+        a.insert(50, 98, 2);
+        a.insert(52, 50, 48);
+        let mut b = OffsetMap::identity();
+        b.insert(0, 15, 37);
+        b.insert(37, 52, 2);
+        b.insert(39, 0, 15);
+
+        let composed = a.then(&b);
+        for x in 0..200 {
+            assert_eq!(b.map(a.map(x)), composed.map(x), "{x}");
+        }
+        let seeds = set(&[(40, 60), (90, 110)]);
+        assert_eq!(b.map_set(&a.map_set(&seeds)), composed.map_set(&seeds));
+        assert_eq!(
+            OffsetMap::identity(),
+            OffsetMap::identity().then(&OffsetMap::identity())
+        );
+        assert_eq!(a, a.then(&OffsetMap::identity()));
+        assert_eq!(a, OffsetMap::identity().then(&a));
+    }
+
+    #[test]
+    fn huge_intervals() {
+        let mut m = OffsetMap::identity();
+        m.insert(0, 1 << 62, 1 << 40);
+        let huge = set(&[(0, u64::MAX)]);
+        // the moved numbers land on numbers that stay where they are
+        assert_eq!(u64::MAX - (1 << 40), m.map_set(&huge).len());
+        assert_eq!(Some(0), m.map_set(&set(&[(1 << 62, u64::MAX)])).min());
+    }
+}
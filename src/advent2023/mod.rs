@@ -1,2 +1,170 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Result;
+
 pub mod day1;
-mod day2;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod grid;
+pub mod interval;
+
+/// Common shape of every day's puzzle. The input is parsed once and both parts are solved from the
+/// parsed representation so the parse and solve steps can be run and measured separately.
+pub trait Solution {
+    /// Day of the month the puzzle was released on.
+    const DAY: u8;
+    /// Title of the puzzle as shown on the website.
+    const TITLE: &'static str;
+
+    /// Parsed representation of the puzzle input shared by both parts, which may be solved from
+    /// several threads at once.
+    type Input: Sync;
+    /// Answer produced by either part.
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part_1(input: &Self::Input) -> Result<Self::Answer>;
+    fn part_2(input: &Self::Input) -> Result<Self::Answer>;
+}
+
+/// Each puzzle is made up of two parts solved from the same input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("unknown part {s}, expected 1 or 2")),
+        }
+    }
+}
+
+/// Puzzle input that has already been parsed by a [Solution], with the answers erased to Strings so
+/// that days with different types can be stored and run together.
+pub trait ParsedInput: Sync {
+    fn part_1(&self) -> Result<String>;
+    fn part_2(&self) -> Result<String>;
+
+    fn solve(&self, part: Part) -> Result<String> {
+        match part {
+            Part::One => self.part_1(),
+            Part::Two => self.part_2(),
+        }
+    }
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> ParsedInput for Parsed<S> {
+    fn part_1(&self) -> Result<String> {
+        S::part_1(&self.0).map(|a| a.to_string())
+    }
+
+    fn part_2(&self) -> Result<String> {
+        S::part_2(&self.0).map(|a| a.to_string())
+    }
+}
+
+/// Entry in the registry of implemented days. See [DAYS].
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    parse: fn(&str) -> Result<Box<dyn ParsedInput>>,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>() -> Self {
+        Day {
+            number: S::DAY,
+            title: S::TITLE,
+            parse: parse_erased::<S>,
+        }
+    }
+
+    /// Parses the input with the day's [Solution] so that either part can be solved from it.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn ParsedInput>> {
+        (self.parse)(input)
+    }
+}
+
+fn parse_erased<S: Solution + 'static>(input: &str) -> Result<Box<dyn ParsedInput>> {
+    Ok(Box::new(Parsed::<S>(S::parse(input)?)))
+}
+
+/// Every day implemented so far, in order.
+pub static DAYS: [Day; 7] = [
+    Day::new::<day1::Trebuchet>(),
+    Day::new::<day2::CubeConundrum>(),
+    Day::new::<day3::GearRatios>(),
+    Day::new::<day4::Scratchcards>(),
+    Day::new::<day5::Fertilizer>(),
+    Day::new::<day6::WaitForIt>(),
+    Day::new::<day7::CamelCards>(),
+];
+
+/// Looks up an implemented day by its number.
+pub fn get_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_day, Part, DAYS};
+
+    #[test]
+    fn registry_is_in_order() {
+        let numbers: Vec<_> = DAYS.iter().map(|d| d.number).collect();
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7], numbers)
+    }
+
+    #[test]
+    fn registry_runs_day() {
+        let day = get_day(1).expect("day 1");
+        let parsed = day.parse("1abc2\ntreb7uchet").unwrap();
+        assert_eq!(Ok("89".to_string()), parsed.part_1())
+    }
+
+    #[test]
+    fn registry_solves_by_part() {
+        let day = get_day(1).expect("day 1");
+        let parsed = day.parse("two1nine").unwrap();
+        assert_eq!(Ok("29".to_string()), parsed.solve(Part::Two))
+    }
+
+    #[test]
+    fn part_from_str() {
+        assert_eq!(Ok(Part::One), "1".parse());
+        assert_eq!(Ok(Part::Two), "2".parse());
+        assert_eq!(true, "3".parse::<Part>().is_err())
+    }
+
+    #[test]
+    fn registry_unknown_day() {
+        assert_eq!(true, get_day(25).is_none())
+    }
+}
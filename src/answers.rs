@@ -0,0 +1,392 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::advent2023::{get_day, Part};
+use crate::error::{Error, Result};
+use crate::input::{cache_dir, write_atomically, InputStore, YEAR};
+
+/// Name of the manifest file in the input cache directory.
+pub const MANIFEST: &str = "expected.tsv";
+
+const HEADER: &str = "# year\tday\tpart\tanswer\texample";
+
+/// An answer known to be right. Without an example it is the answer for the day's puzzle input,
+/// otherwise for the example input stored in the file of that name next to the puzzle inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub example: Option<String>,
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} day {} part {}", self.year, self.day, self.part)?;
+        match &self.example {
+            Some(example) => write!(f, " ({example})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An expected answer that the solver did not give, along with what it gave instead or why it
+/// could not give anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub expected: Expected,
+    pub found: std::result::Result<String, String>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let answer = &self.expected.answer;
+        match &self.found {
+            Ok(found) => write!(f, "{}: expected {answer} but got {found}", self.expected),
+            Err(e) => write!(f, "{}: expected {answer} but {e}", self.expected),
+        }
+    }
+}
+
+/// Answers that are known to be right, kept in a file with a tab separated line per answer holding
+/// the year, day, part, answer and optionally the name of the example input the answer is for.
+/// Lines starting with `#` are comments, which are kept along with blank lines when answers are
+/// recorded.
+pub struct Manifest {
+    path: PathBuf,
+    entries: Vec<Expected>,
+    /// Every line of the file in order, so it can be written back as it was read.
+    lines: Vec<Line>,
+}
+
+/// A line of the manifest file.
+enum Line {
+    /// A comment or blank line, kept as it is.
+    Text(String),
+    /// The entry at this index.
+    Entry(usize),
+}
+
+impl Manifest {
+    /// Reads the manifest from the file, which is empty if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => format!("{HEADER}\n"),
+            Err(e) => return Err(Error::io(&path, e)),
+        };
+
+        let mut entries = Vec::new();
+        let mut lines = Vec::new();
+        for (index, l) in data.lines().enumerate() {
+            if l.trim().is_empty() || l.starts_with('#') {
+                lines.push(Line::Text(l.to_string()));
+            } else {
+                lines.push(Line::Entry(entries.len()));
+                entries.push(parse_expected(index + 1, l)?);
+            }
+        }
+
+        Ok(Manifest {
+            path,
+            entries,
+            lines,
+        })
+    }
+
+    /// The manifest in the input cache directory, which defaults to ./test and can be changed with
+    /// `AOC_CACHE_DIR`.
+    pub fn from_env() -> Result<Self> {
+        Manifest::open(cache_dir().join(MANIFEST))
+    }
+
+    pub fn entries(&self) -> &[Expected] {
+        &self.entries
+    }
+
+    /// Records the answer for the day's puzzle input, replacing the one already recorded, and
+    /// writes the manifest back to its file. A new answer goes before the first entry that sorts
+    /// after it, leaving every other line where it was. Returns the answer that was replaced, if
+    /// any.
+    pub fn record(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &str,
+    ) -> Result<Option<String>> {
+        let existing = self
+            .entries
+            .iter_mut()
+            .find(|e| e.year == year && e.day == day && e.part == part && e.example.is_none());
+        let replaced = match existing {
+            Some(entry) => Some(std::mem::replace(&mut entry.answer, answer.to_string())),
+            None => {
+                let expected = Expected {
+                    year,
+                    day,
+                    part,
+                    answer: answer.to_string(),
+                    example: None,
+                };
+                let at = self
+                    .lines
+                    .iter()
+                    .position(|l| match l {
+                        Line::Entry(i) => sort_key(&self.entries[*i]) > sort_key(&expected),
+                        Line::Text(_) => false,
+                    })
+                    .unwrap_or(self.lines.len());
+                self.lines.insert(at, Line::Entry(self.entries.len()));
+                self.entries.push(expected);
+                None
+            }
+        };
+
+        self.save()?;
+        Ok(replaced)
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut data = String::new();
+        for line in &self.lines {
+            match line {
+                Line::Text(text) => data += text,
+                Line::Entry(i) => {
+                    let e = &self.entries[*i];
+                    data += &format!("{}\t{}\t{}\t{}", e.year, e.day, e.part, e.answer);
+                    if let Some(example) = &e.example {
+                        data += &format!("\t{example}");
+                    }
+                }
+            }
+            data += "\n";
+        }
+        write_atomically(&self.path, &data)
+    }
+
+    /// Runs the solver of every expected answer for the store's year against its input, returning
+    /// every answer that did not match. Answers for other years or for days that are not
+    /// implemented are mismatches too, so nothing in the manifest goes unchecked. Puzzle inputs come
+    /// from the store and examples from files in its cache directory.
+    pub fn check(&self, store: &InputStore) -> Vec<Mismatch> {
+        self.entries
+            .iter()
+            .filter_map(|expected| {
+                let found = solve(store, expected);
+                match &found {
+                    Ok(answer) if *answer == expected.answer => None,
+                    _ => Some(Mismatch {
+                        expected: expected.clone(),
+                        found,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Order of the entries in the manifest: by day, with the puzzle input before the examples.
+fn sort_key(e: &Expected) -> (u16, u8, &Option<String>, Part) {
+    (e.year, e.day, &e.example, e.part)
+}
+
+fn solve(store: &InputStore, expected: &Expected) -> std::result::Result<String, String> {
+    if expected.year != YEAR {
+        return Err(format!("only {YEAR} is implemented"));
+    }
+    let day = get_day(expected.day).ok_or("the day is not implemented")?;
+    let input = match &expected.example {
+        Some(example) => {
+            let path = store.cache_dir().join(example);
+            fs::read_to_string(&path).map_err(|e| Error::io(&path, e))
+        }
+        None => store.get(day.number),
+    }
+    .map_err(|e| format!("the input could not be read: {e}"))?;
+
+    day.parse(&input)
+        .and_then(|parsed| parsed.solve(expected.part))
+        .map_err(|e| format!("it failed: {e}"))
+}
+
+fn parse_expected(line_number: usize, line: &str) -> Result<Expected> {
+    let fields: Vec<_> = line.split('\t').collect();
+    let parse_error = |token: &str, expected| Error::field(line_number, line, token, expected);
+
+    let (year, day, part, answer, example) = match fields[..] {
+        [year, day, part, answer] => (year, day, part, answer, None),
+        [year, day, part, answer, example] => (year, day, part, answer, Some(example)),
+        _ => {
+            return Err(parse_error(
+                line,
+                "year, day, part, answer and an optional example",
+            ))
+        }
+    };
+    if answer.is_empty() {
+        return Err(parse_error(answer, "an answer"));
+    }
+    Ok(Expected {
+        year: year.parse().map_err(|_| parse_error(year, "a year"))?,
+        day: day.parse().map_err(|_| parse_error(day, "a day"))?,
+        part: part.parse().map_err(|_| parse_error(part, "a part"))?,
+        answer: answer.to_string(),
+        example: example.map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Expected, Manifest, Mismatch, MANIFEST};
+    use crate::advent2023::Part;
+    use crate::error::Error;
+    use crate::input::{InputStore, DEFAULT_CACHE_DIR, YEAR};
+    use crate::test::temp_dir;
+
+    /// Every answer in the manifest checked against the solvers, reporting all mismatches at once.
+    #[test]
+    fn manifest_answers() {
+        let store = InputStore::new(YEAR, DEFAULT_CACHE_DIR);
+        let manifest = Manifest::open(store.cache_dir().join(MANIFEST)).unwrap();
+        assert_eq!(false, manifest.entries().is_empty());
+
+        let mismatches: Vec<_> = manifest
+            .check(&store)
+            .iter()
+            .map(Mismatch::to_string)
+            .collect();
+        assert_eq!(Vec::<String>::new(), mismatches)
+    }
+
+    #[test]
+    fn reads_entries() {
+        let dir = temp_dir("reads_entries");
+        fs::write(
+            dir.join(MANIFEST),
+            "# comment\n2023\t1\t2\t281\tday1_example.txt\n\n2023\t2\t1\t8\n",
+        )
+        .unwrap();
+
+        let manifest = Manifest::open(dir.join(MANIFEST)).unwrap();
+        assert_eq!(
+            vec![
+                Expected {
+                    year: 2023,
+                    day: 1,
+                    part: Part::Two,
+                    answer: "281".to_string(),
+                    example: Some("day1_example.txt".to_string()),
+                },
+                Expected {
+                    year: 2023,
+                    day: 2,
+                    part: Part::One,
+                    answer: "8".to_string(),
+                    example: None,
+                },
+            ],
+            manifest.entries()
+        );
+    }
+
+    #[test]
+    fn bad_line() {
+        let dir = temp_dir("manifest_bad_line");
+        fs::write(dir.join(MANIFEST), "2023\t1\t3\t281\n").unwrap();
+        assert_eq!(
+            Some(Error::Parse {
+                line: 1,
+                column: 8,
+                token: "3".to_string(),
+                expected: "a part",
+                excerpt: "2023\t1\t3\t281".to_string(),
+            }),
+            Manifest::open(dir.join(MANIFEST)).err()
+        )
+    }
+
+    #[test]
+    fn record_replaces_and_sorts() {
+        let dir = temp_dir("record_replaces_and_sorts");
+        let path = dir.join(MANIFEST);
+        let mut manifest = Manifest::open(&path).unwrap();
+        assert_eq!(Ok(None), manifest.record(2023, 2, Part::One, "8"));
+        assert_eq!(Ok(None), manifest.record(2023, 1, Part::Two, "280"));
+        assert_eq!(
+            Ok(Some("280".to_string())),
+            manifest.record(2023, 1, Part::Two, "281")
+        );
+
+        assert_eq!(
+            "# year\tday\tpart\tanswer\texample\n2023\t1\t2\t281\n2023\t2\t1\t8\n",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(2, Manifest::open(&path).unwrap().entries().len());
+    }
+
+    #[test]
+    fn record_keeps_comments() {
+        let dir = temp_dir("record_keeps_comments");
+        let path = dir.join(MANIFEST);
+        fs::write(
+            &path,
+            "# year\tday\tpart\tanswer\texample\n# checked by hand\n2023\t1\t1\t1\n\n# day 3\n2023\t3\t1\t3\n",
+        )
+        .unwrap();
+        let mut manifest = Manifest::open(&path).unwrap();
+        assert_eq!(
+            Ok(Some("1".to_string())),
+            manifest.record(2023, 1, Part::One, "11")
+        );
+        assert_eq!(Ok(None), manifest.record(2023, 2, Part::One, "2"));
+        assert_eq!(Ok(None), manifest.record(2023, 4, Part::One, "4"));
+
+        assert_eq!(
+            "# year\tday\tpart\tanswer\texample\n# checked by hand\n2023\t1\t1\t11\n\n# day 3\n2023\t2\t1\t2\n2023\t3\t1\t3\n2023\t4\t1\t4\n",
+            fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn check_reports_every_mismatch() {
+        let dir = temp_dir("check_reports_every_mismatch");
+        fs::write(dir.join("day1_input.txt"), "1abc2\ntreb7uchet\n").unwrap();
+        fs::write(dir.join("example.txt"), "two1nine\n").unwrap();
+        fs::write(
+            dir.join(MANIFEST),
+            "2023\t1\t1\t89\n2023\t1\t2\t90\n2023\t1\t2\t29\texample.txt\n\
+             2023\t1\t1\t1\tmissing.txt\n2023\t25\t1\t1\n2022\t1\t1\t1\n",
+        )
+        .unwrap();
+
+        let store = InputStore::new(YEAR, &dir);
+        let manifest = Manifest::open(dir.join(MANIFEST)).unwrap();
+        let mismatches: Vec<_> = manifest
+            .check(&store)
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(4, mismatches.len(), "{mismatches:?}");
+        assert_eq!("2023 day 1 part 2: expected 90 but got 89", mismatches[0]);
+        assert_eq!(
+            true,
+            mismatches[1].starts_with(
+                "2023 day 1 part 1 (missing.txt): expected 1 but the input could not be read"
+            )
+        );
+        assert_eq!(
+            "2023 day 25 part 1: expected 1 but the day is not implemented",
+            mismatches[2]
+        );
+        assert_eq!(
+            "2022 day 1 part 1: expected 1 but only 2023 is implemented",
+            mismatches[3]
+        );
+    }
+}
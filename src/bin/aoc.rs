@@ -0,0 +1,286 @@
+use std::io::Read;
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{env, fs, io};
+
+use adventofcode2023::advent2023::{get_day, Day, Part, DAYS};
+use adventofcode2023::answers::{Manifest, MANIFEST};
+use adventofcode2023::error::Error;
+use adventofcode2023::input::{InputStore, YEAR};
+use adventofcode2023::run::{run_days, Mode};
+use adventofcode2023::submit::{Outcome, Submitter};
+
+const USAGE: &str = "Usage: aoc <day> [--part <1|2>] [--input <path|->] [--submit] [--record]
+       aoc all [--part <1|2>] [--input <path>] [--sequential] [--markdown] [--record]
+
+Runs the solver for a day and prints the answers along with how long each step took. Both parts
+are run unless --part is given. The input is read from the input cache unless --input is given,
+where `-` reads it from stdin.
+
+With `all` every implemented day is run at once, each day and part on a thread of its own, and a
+table of the answers and times is printed with the total wall time of the run. --sequential runs
+one step after another for more accurate times, and --markdown prints the table as Markdown.
+
+Inputs missing from the cache are downloaded with the session token in AOC_SESSION. The cache
+directory defaults to ./test and can be changed with AOC_CACHE_DIR.
+
+With --submit the answer to a single day and part is submitted, unless answers.tsv in the cache
+directory already shows it is wrong. Every submission is recorded there.
+
+With --record the answers are stored as the verified answers in expected.tsv in the cache
+directory, which the test suite checks the solvers against. Only answers for the cached puzzle
+input can be recorded.";
+
+/// Which days the runner was asked to run.
+enum Target {
+    All,
+    Day(u8),
+}
+
+/// Where the puzzle input is read from.
+enum Source {
+    Default,
+    Stdin,
+    File(String),
+}
+
+struct Args {
+    target: Target,
+    parts: Vec<Part>,
+    source: Source,
+    submit: bool,
+    record: bool,
+    mode: Mode,
+    markdown: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut target: Option<Target> = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut source = Source::Default;
+    let mut submit = false;
+    let mut record = false;
+    let mut mode = Mode::Parallel;
+    let mut markdown = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let part = args.next().ok_or("--part needs a value")?;
+                parts = vec![part.parse()?];
+            }
+            "--input" | "-i" => {
+                let path = args.next().ok_or("--input needs a value")?;
+                source = match path.as_str() {
+                    "-" => Source::Stdin,
+                    _ => Source::File(path.to_string()),
+                };
+            }
+            "--submit" => submit = true,
+            "--record" => record = true,
+            "--sequential" => mode = Mode::Sequential,
+            "--markdown" => markdown = true,
+            "all" if target.is_none() => target = Some(Target::All),
+            day if target.is_none() => {
+                let day = day.parse().map_err(|_| format!("invalid day {day}"))?;
+                target = Some(Target::Day(day));
+            }
+            other => return Err(format!("unexpected argument {other}")),
+        }
+    }
+
+    let target = target.ok_or("missing day")?;
+    if matches!((&target, &source), (Target::All, Source::Stdin)) {
+        return Err("stdin can only be used as input when running a single day".to_string());
+    }
+    if submit && (matches!(target, Target::All) || parts.len() != 1) {
+        return Err("--submit needs a single day and --part".to_string());
+    }
+    if (mode == Mode::Sequential || markdown) && !matches!(target, Target::All) {
+        return Err("--sequential and --markdown can only be used with all".to_string());
+    }
+    if record && !matches!(source, Source::Default) {
+        return Err("--record can not be used with --input".to_string());
+    }
+
+    Ok(Args {
+        target,
+        parts,
+        source,
+        submit,
+        record,
+        mode,
+        markdown,
+    })
+}
+
+fn read_input(day: &Day, source: &Source) -> Result<String, String> {
+    match source {
+        Source::Default => InputStore::from_env()
+            .get(day.number)
+            .map_err(|e| format!("day {} input: {e}", day.number)),
+        Source::File(path) => {
+            fs::read_to_string(path).map_err(|e| format!("could not read {path}: {e}"))
+        }
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("could not read stdin: {e}"))?;
+            Ok(input)
+        }
+    }
+}
+
+/// Runs the requested parts of a single day, printing each answer as it is found. The answers are
+/// returned along with the part they belong to.
+fn run_day(day: &Day, parts: &[Part], source: &Source) -> Result<Vec<(Part, String)>, String> {
+    let input = read_input(day, source)?;
+    println!("Day {}: {}", day.number, day.title);
+
+    let start = Instant::now();
+    let parsed = day
+        .parse(&input)
+        .map_err(|e| format!("day {} input: {e}", day.number))?;
+    println!("  parse:  {:?}", start.elapsed());
+
+    let mut answers = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let answer = parsed
+            .solve(*part)
+            .map_err(|e| format!("day {} part {part}: {e}", day.number))?;
+        println!("  part {part}: {answer} ({:?})", start.elapsed());
+        answers.push((*part, answer));
+    }
+
+    Ok(answers)
+}
+
+fn submit(day: &Day, part: Part, answer: &str) -> Result<(), String> {
+    let mut submitter = Submitter::from_env().map_err(|e| e.to_string())?;
+    match submitter
+        .submit(day.number, part, answer)
+        .map_err(|e| e.to_string())?
+    {
+        Outcome::Submitted(verdict) => {
+            println!("  submitted: {verdict}");
+            if verdict.is_wrong() {
+                Err(format!(
+                    "day {} part {part}: answer was {verdict}",
+                    day.number
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        Outcome::Rejected(rejection) => Err(format!("not submitted: {rejection}")),
+    }
+}
+
+/// Stores the answers as the verified answers for the day's puzzle input.
+fn record(day: &Day, answers: &[(Part, String)]) -> Result<(), String> {
+    let mut manifest = Manifest::from_env().map_err(|e| e.to_string())?;
+    for (part, answer) in answers {
+        let replaced = manifest
+            .record(YEAR, day.number, *part, answer)
+            .map_err(|e| format!("could not record in {MANIFEST}: {e}"))?;
+        match replaced {
+            Some(old) if old != *answer => {
+                println!(
+                    "  recorded day {} part {part}: {answer} (was {old})",
+                    day.number
+                )
+            }
+            _ => println!("  recorded day {} part {part}: {answer}", day.number),
+        }
+    }
+    Ok(())
+}
+
+/// Runs every implemented day at once and prints a table of the answers and times.
+fn run_all(args: &Args) -> ExitCode {
+    let days: Vec<&Day> = DAYS.iter().collect();
+    let summary = run_days(&days, &args.parts, args.mode, |day| match &args.source {
+        Source::File(path) => fs::read_to_string(path).map_err(|e| Error::Io {
+            path: path.to_string(),
+            message: e.to_string(),
+        }),
+        _ => InputStore::from_env().get(day.number),
+    });
+    if args.markdown {
+        print!("{}", summary.to_markdown());
+    } else {
+        print!("{}", summary.to_table());
+    }
+
+    let mut failed = !summary.is_ok();
+    if args.record {
+        for run in &summary.days {
+            let day = get_day(run.number).expect("ran an implemented day");
+            let answers: Vec<_> = run
+                .parts
+                .iter()
+                .filter_map(|p| Some((p.part, p.answer.clone().ok()?)))
+                .collect();
+            if let Err(e) = record(day, &answers) {
+                eprintln!("error: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let day = match args.target {
+        Target::All => return run_all(&args),
+        Target::Day(number) => match get_day(number) {
+            Some(day) => day,
+            None => {
+                eprintln!("error: day {number} is not implemented");
+                return ExitCode::from(2);
+            }
+        },
+    };
+
+    let start = Instant::now();
+    let result = run_day(day, &args.parts, &args.source).and_then(|answers| {
+        if args.record {
+            record(day, &answers)?;
+        }
+        match (args.submit, answers.as_slice()) {
+            (true, [(part, answer)]) => submit(day, *part, answer),
+            _ => Ok(()),
+        }
+    });
+    println!("total: {:?}", start.elapsed());
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
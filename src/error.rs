@@ -0,0 +1,151 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors produced while parsing puzzle inputs or solving them. Lines and columns are 1-based so
+/// they can be matched directly against the input file in an editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A token did not match what was expected at that position of the line. The column counts
+    /// characters, and the excerpt is the whole line so the token can be pointed at when displayed.
+    Parse {
+        line: usize,
+        column: usize,
+        token: String,
+        expected: &'static str,
+        excerpt: String,
+    },
+    /// A line that must contain a digit did not have any.
+    NoDigitFound { line: usize },
+    /// A number or a running total did not fit in the type used to hold it.
+    Overflow { line: usize },
+    /// A JSON document could not be read. The message says where and why.
+    Json { message: String },
+    /// Reading or writing a file failed.
+    Io { path: String, message: String },
+    /// Reading a line from a stream of input failed.
+    Read { line: usize, message: String },
+    /// A request to the Advent of Code server failed.
+    Http { url: String, message: String },
+    /// The server needs to be contacted but there is no session token to log in with.
+    MissingSession,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                token,
+                expected,
+                excerpt,
+            } => {
+                if token.is_empty() {
+                    write!(f, "line {line}, column {column}: expected {expected} but found the end of the line")?;
+                } else {
+                    write!(
+                        f,
+                        "line {line}, column {column}: expected {expected} but found {token:?}"
+                    )?;
+                }
+                if !excerpt.is_empty() {
+                    let padding = " ".repeat(column.saturating_sub(1));
+                    let marker = "^".repeat(token.chars().count().max(1));
+                    write!(f, "\n  {excerpt}\n  {padding}{marker}")?;
+                }
+                Ok(())
+            }
+            Error::NoDigitFound { line } => write!(f, "line {line}: no digit found"),
+            Error::Overflow { line } => write!(f, "line {line}: number too large"),
+            Error::Json { message } => write!(f, "invalid JSON: {message}"),
+            Error::Io { path, message } => write!(f, "{path}: {message}"),
+            Error::Read { line, message } => write!(f, "line {line}: could not be read: {message}"),
+            Error::Http { url, message } => write!(f, "{url}: {message}"),
+            Error::MissingSession => write!(f, "no session token is set, see AOC_SESSION"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Parse error for a field of a line, such as a column of a tab separated line, where the token
+    /// is a slice of the line so that the column it starts at can be worked out.
+    pub(crate) fn field(
+        line_number: usize,
+        line: &str,
+        token: &str,
+        expected: &'static str,
+    ) -> Self {
+        let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+        Error::Parse {
+            line: line_number,
+            column: line[..offset].chars().count() + 1,
+            token: token.to_string(),
+            expected,
+            excerpt: line.to_string(),
+        }
+    }
+
+    pub(crate) fn io(path: &std::path::Path, e: std::io::Error) -> Self {
+        Error::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn display_parse_error() {
+        let e = Error::Parse {
+            line: 3,
+            column: 6,
+            token: "x".to_string(),
+            expected: "a game id",
+            excerpt: String::new(),
+        };
+        assert_eq!(
+            "line 3, column 6: expected a game id but found \"x\"",
+            e.to_string()
+        )
+    }
+
+    #[test]
+    fn display_parse_error_with_excerpt() {
+        let e = Error::Parse {
+            line: 1,
+            column: 17,
+            token: "3red".to_string(),
+            expected: "a cube count",
+            excerpt: "Game 1: 3 blue, 3red".to_string(),
+        };
+        assert_eq!(
+            "line 1, column 17: expected a cube count but found \"3red\"
+  Game 1: 3 blue, 3red
+                  ^^^^",
+            e.to_string()
+        )
+    }
+
+    #[test]
+    fn display_parse_error_at_end_of_line() {
+        let e = Error::Parse {
+            line: 2,
+            column: 8,
+            token: String::new(),
+            expected: "':'",
+            excerpt: "Game 12".to_string(),
+        };
+        assert_eq!(
+            "line 2, column 8: expected ':' but found the end of the line
+  Game 12
+         ^",
+            e.to_string()
+        )
+    }
+}
@@ -0,0 +1,203 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Year of the puzzles this crate solves.
+pub const YEAR: u16 = 2023;
+/// Directory inputs are cached in unless `AOC_CACHE_DIR` is set.
+pub const DEFAULT_CACHE_DIR: &str = "./test";
+/// Server inputs are downloaded from unless `AOC_BASE_URL` is set.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Sent with every request to the server so its operators know where the traffic comes from.
+pub(crate) const USER_AGENT: &str = "github.com/AnthonyMBonafide/advent-of-code-rust-2023";
+
+/// Directory inputs and the files kept next to them are cached in, from `AOC_CACHE_DIR` or
+/// [DEFAULT_CACHE_DIR].
+pub fn cache_dir() -> PathBuf {
+    PathBuf::from(env::var("AOC_CACHE_DIR").unwrap_or(DEFAULT_CACHE_DIR.to_string()))
+}
+
+/// Resolves puzzle inputs from a cache directory, downloading them from the server with the user's
+/// session token the first time a day is requested. Once a day is cached it is never downloaded
+/// again.
+pub struct InputStore {
+    year: u16,
+    cache_dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+}
+
+impl InputStore {
+    pub fn new(year: u16, cache_dir: impl Into<PathBuf>) -> Self {
+        InputStore {
+            year,
+            cache_dir: cache_dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+        }
+    }
+
+    /// Store for the current year configured from the `AOC_CACHE_DIR`, `AOC_BASE_URL` and
+    /// `AOC_SESSION` environment variables.
+    pub fn from_env() -> Self {
+        let mut store = InputStore::new(YEAR, cache_dir());
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            store = store.with_base_url(base_url);
+        }
+        if let Ok(session) = env::var("AOC_SESSION") {
+            store = store.with_session(session);
+        }
+        store
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    /// Directory the inputs are cached in.
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Location the input for the day is cached at.
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{day}_input.txt"))
+    }
+
+    /// Returns the input for the day, downloading and caching it if it is not cached yet.
+    pub fn get(&self, day: u8) -> Result<String> {
+        if let Some(input) = self.cached(day)? {
+            return Ok(input);
+        }
+
+        let input = self.fetch(day)?;
+        write_atomically(&self.cache_path(day), &input)?;
+        Ok(input)
+    }
+
+    /// Returns the input for the day if it is cached, without ever downloading it.
+    pub fn cached(&self, day: u8) -> Result<Option<String>> {
+        let path = self.cache_path(day);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| Error::io(&path, e))
+    }
+
+    fn fetch(&self, day: u8) -> Result<String> {
+        let session = self.session.as_ref().ok_or(Error::MissingSession)?;
+        let url = format!("{}/{}/day/{day}/input", self.base_url, self.year);
+        let http_error = |message: String| Error::Http {
+            url: url.clone(),
+            message,
+        };
+
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|e| http_error(e.to_string()))?;
+        response
+            .into_string()
+            .map_err(|e| http_error(e.to_string()))
+    }
+}
+
+/// Writes the file through a temporary file in the same directory so that a failed or interrupted
+/// write never leaves a partial input in the cache.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+
+    fs::write(&tmp, contents).map_err(|e| Error::io(&tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        Error::io(path, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::InputStore;
+    use crate::error::Error;
+    use crate::test::{serve_http, temp_dir};
+
+    #[test]
+    fn reads_cached_input() {
+        let dir = temp_dir("reads_cached_input");
+        fs::write(dir.join("day3_input.txt"), "cached").unwrap();
+
+        let store = InputStore::new(2023, &dir);
+        assert_eq!(Ok("cached".to_string()), store.get(3))
+    }
+
+    #[test]
+    fn cached_never_downloads() {
+        let dir = temp_dir("cached_never_downloads");
+        fs::write(dir.join("day3_input.txt"), "cached").unwrap();
+
+        let store = InputStore::new(2023, &dir).with_session("abc");
+        assert_eq!(Ok(Some("cached".to_string())), store.cached(3));
+        assert_eq!(Ok(None), store.cached(4));
+    }
+
+    #[test]
+    fn missing_session() {
+        let dir = temp_dir("missing_session");
+        let store = InputStore::new(2023, &dir);
+        assert_eq!(Err(Error::MissingSession), store.get(3))
+    }
+
+    #[test]
+    fn fetches_and_caches_input() {
+        let dir = temp_dir("fetches_and_caches_input");
+        let server = serve_http(vec![(200, "467..114..\n".to_string())]);
+
+        let store = InputStore::new(2023, &dir)
+            .with_base_url(&server.url)
+            .with_session("abc");
+        assert_eq!(Ok("467..114..\n".to_string()), store.get(3));
+        // the second call is answered from the cache, the server only has a single response
+        assert_eq!(Ok("467..114..\n".to_string()), store.get(3));
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!(true, requests[0].starts_with("GET /2023/day/3/input "));
+        assert_eq!(true, requests[0].contains("session=abc"));
+        assert_eq!(
+            "467..114..\n",
+            fs::read_to_string(dir.join("day3_input.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn failed_fetch_is_not_cached() {
+        let dir = temp_dir("failed_fetch_is_not_cached");
+        let server = serve_http(vec![(404, "Not found".to_string())]);
+
+        let store = InputStore::new(2023, &dir)
+            .with_base_url(&server.url)
+            .with_session("abc");
+        assert_eq!(true, matches!(store.get(25), Err(Error::Http { .. })));
+        assert_eq!(false, dir.join("day25_input.txt").exists());
+        assert_eq!(0, fs::read_dir(&dir).unwrap().count());
+    }
+}
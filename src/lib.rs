@@ -1,12 +1,89 @@
 pub mod advent2023;
+pub mod answers;
+pub mod error;
+pub mod input;
+pub mod run;
+pub mod submit;
+pub mod timing;
 
 #[cfg(test)]
 mod test {
-    use std::fs;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::{fs, thread};
 
-    /// Helps loading in test data. This can be changed in the future to pull the data directly
-    /// from the server if it is not already in the file system.
+    use crate::input::InputStore;
+
+    /// Helps loading in test data. Inputs missing from the cache are downloaded when a session
+    /// token is configured, see [InputStore::from_env].
     pub fn get_day_input(day_number: u8) -> String {
-        fs::read_to_string(format!("./test/day{day_number}_input.txt")).expect("data")
+        InputStore::from_env().get(day_number).expect("data")
+    }
+
+    /// Creates an empty directory for a test to write into.
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2023-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir");
+        dir
+    }
+
+    /// Local stand-in for the Advent of Code server, see [serve_http].
+    pub struct TestServer {
+        pub url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TestServer {
+        /// Every request received so far, including the request line, headers and body.
+        pub fn requests(&self) -> Vec<String> {
+            self.requests.lock().expect("requests").clone()
+        }
+    }
+
+    /// Starts an HTTP server on a free local port that answers one connection with each of the
+    /// given (status, body) responses in order and then stops accepting connections.
+    pub fn serve_http(responses: Vec<(u16, String)>) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}", listener.local_addr().expect("address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().expect("connection");
+                let mut reader = BufReader::new(stream.try_clone().expect("stream"));
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("request line");
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:")
+                    {
+                        content_length = length.trim().parse().expect("content length");
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).expect("request body");
+                request.push_str(&String::from_utf8_lossy(&content));
+                received.lock().expect("requests").push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Test\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .expect("response");
+            }
+        });
+
+        TestServer { url, requests }
     }
 }
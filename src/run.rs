@@ -0,0 +1,381 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::advent2023::{Day, Part};
+use crate::error::Result;
+
+/// How the days and their parts are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Every day on its own thread, with both parts of a day solved on threads of their own once it
+    /// is parsed. Fastest overall, but the days compete for cores so each time is less accurate.
+    Parallel,
+    /// One step after another on the current thread, for times that are comparable between runs.
+    Sequential,
+}
+
+/// The answer to a single part and how long it took to solve from the parsed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<String>,
+    pub time: Duration,
+}
+
+/// The outcome of running a single day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRun {
+    pub number: u8,
+    pub title: &'static str,
+    /// Time spent parsing the input, or why the input could not be read or parsed.
+    pub parse: Result<Duration>,
+    /// Every part that was run, in order. Empty when the input could not be parsed.
+    pub parts: Vec<PartRun>,
+    /// Wall time from the start of parsing until the last part was solved.
+    pub wall: Duration,
+}
+
+/// The outcome of running several days, which can be written out as a text or Markdown table with
+/// a row for every part of every day and a total at the bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub mode: Mode,
+    pub days: Vec<DayRun>,
+    /// Wall time of the whole run. Reading the inputs is included, as it happens on the day threads.
+    pub wall: Duration,
+}
+
+/// Runs the parts of every day on the input `input` gives for it. A day whose input can not be read
+/// or parsed, or whose part fails, is reported in the summary without stopping the other days.
+pub fn run_days(
+    days: &[&Day],
+    parts: &[Part],
+    mode: Mode,
+    input: impl Fn(&Day) -> Result<String> + Sync,
+) -> Summary {
+    let start = Instant::now();
+    let run = |day: &Day| run_day(day, parts, mode, input(day));
+    let days = match mode {
+        Mode::Sequential => days.iter().map(|day| run(day)).collect(),
+        Mode::Parallel => thread::scope(|scope| {
+            let run = &run;
+            let handles: Vec<_> = days
+                .iter()
+                .map(|day| scope.spawn(move || run(day)))
+                .collect();
+            handles.into_iter().map(join).collect()
+        }),
+    };
+
+    Summary {
+        mode,
+        days,
+        wall: start.elapsed(),
+    }
+}
+
+fn run_day(day: &Day, parts: &[Part], mode: Mode, input: Result<String>) -> DayRun {
+    let start = Instant::now();
+    let parsed = input.and_then(|input| day.parse(&input));
+    let parse = start.elapsed();
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return DayRun {
+                number: day.number,
+                title: day.title,
+                parse: Err(e),
+                parts: Vec::new(),
+                wall: start.elapsed(),
+            }
+        }
+    };
+
+    let solve = |part: Part| {
+        let start = Instant::now();
+        let answer = parsed.solve(part);
+        PartRun {
+            part,
+            answer,
+            time: start.elapsed(),
+        }
+    };
+    let parts = match mode {
+        Mode::Sequential => parts.iter().map(|part| solve(*part)).collect(),
+        Mode::Parallel => thread::scope(|scope| {
+            let solve = &solve;
+            let handles: Vec<_> = parts
+                .iter()
+                .map(|part| scope.spawn(move || solve(*part)))
+                .collect();
+            handles.into_iter().map(join).collect()
+        }),
+    };
+
+    DayRun {
+        number: day.number,
+        title: day.title,
+        parse: Ok(parse),
+        parts,
+        wall: start.elapsed(),
+    }
+}
+
+/// Waits for a thread, passing a panic in a solver on to the caller.
+fn join<T>(handle: thread::ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+impl Summary {
+    /// Whether every day was parsed and every part answered.
+    pub fn is_ok(&self) -> bool {
+        self.days
+            .iter()
+            .all(|d| d.parse.is_ok() && d.parts.iter().all(|p| p.answer.is_ok()))
+    }
+
+    /// Column names followed by a row for every part and a total row. The total of a part is the
+    /// time to parse and solve it, while the total of the run is its wall time, which is less than
+    /// the sum of the others when running in parallel.
+    fn rows(&self) -> Vec<Vec<String>> {
+        let time = |d: Duration| format!("{d:.2?}");
+        let header = ["day", "part", "answer", "parse", "solve", "total"]
+            .map(str::to_string)
+            .to_vec();
+
+        let mut rows = vec![header];
+        for day in &self.days {
+            let name = format!("{} {}", day.number, day.title);
+            let parse = match &day.parse {
+                Ok(parse) => *parse,
+                Err(e) => {
+                    let error = format!("error: {e}");
+                    rows.push(
+                        [
+                            name,
+                            "-".into(),
+                            error,
+                            "-".into(),
+                            "-".into(),
+                            time(day.wall),
+                        ]
+                        .to_vec(),
+                    );
+                    continue;
+                }
+            };
+            for part in &day.parts {
+                let answer = match &part.answer {
+                    Ok(answer) => answer.clone(),
+                    Err(e) => format!("error: {e}"),
+                };
+                rows.push(
+                    [
+                        name.clone(),
+                        part.part.to_string(),
+                        answer,
+                        time(parse),
+                        time(part.time),
+                        time(parse + part.time),
+                    ]
+                    .to_vec(),
+                );
+            }
+        }
+
+        let parse: Duration = self.days.iter().filter_map(|d| d.parse.as_ref().ok()).sum();
+        let solve: Duration = self
+            .days
+            .iter()
+            .flat_map(|d| &d.parts)
+            .map(|p| p.time)
+            .sum();
+        rows.push(
+            [
+                "total".to_string(),
+                String::new(),
+                String::new(),
+                time(parse),
+                time(solve),
+                time(self.wall),
+            ]
+            .to_vec(),
+        );
+        rows
+    }
+
+    /// The summary as a text table with the day and answer lined up to the left and the times to
+    /// the right. Only the first line of each cell is kept, so errors that show where they
+    /// happened on lines of their own do not break up the table.
+    pub fn to_table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .rows()
+            .iter()
+            .map(|row| row.iter().map(|cell| table_cell(cell)).collect())
+            .collect();
+        let widths = widths(&rows);
+        rows.iter()
+            .map(|row| {
+                let cells: Vec<_> = row
+                    .iter()
+                    .zip(&widths)
+                    .enumerate()
+                    .map(|(column, (cell, width))| match column {
+                        0 | 2 => format!("{cell:<width$}"),
+                        _ => format!("{cell:>width$}"),
+                    })
+                    .collect();
+                cells.join("  ").trim_end().to_string() + "\n"
+            })
+            .collect()
+    }
+
+    /// The summary as a Markdown table, with the total row in bold.
+    pub fn to_markdown(&self) -> String {
+        let rows = self.rows();
+        let last = rows.len() - 1;
+        let mut markdown = String::new();
+        for (index, row) in rows.iter().enumerate() {
+            let cells: Vec<_> = row
+                .iter()
+                .map(|cell| match (index == last, cell.is_empty()) {
+                    (true, false) => format!("**{}**", markdown_cell(cell)),
+                    _ => markdown_cell(cell),
+                })
+                .collect();
+            markdown += &format!("| {} |\n", cells.join(" | "));
+            if index == 0 {
+                markdown += "| :-- | --: | :-- | --: | --: | --: |\n";
+            }
+        }
+        markdown
+    }
+}
+
+/// Width of every column in characters.
+fn widths(rows: &[Vec<String>]) -> Vec<usize> {
+    (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// The first line of a text table cell.
+fn table_cell(cell: &str) -> String {
+    cell.lines().next().unwrap_or_default().to_string()
+}
+
+/// Escapes the pipes of a Markdown table cell and keeps it on a single line.
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{run_days, DayRun, Mode, PartRun, Summary};
+    use crate::advent2023::{get_day, Part};
+    use crate::error::Error;
+
+    fn summary() -> Summary {
+        let ms = Duration::from_millis;
+        Summary {
+            mode: Mode::Sequential,
+            days: vec![
+                DayRun {
+                    number: 1,
+                    title: "Trebuchet?!",
+                    parse: Ok(ms(1)),
+                    parts: vec![
+                        PartRun {
+                            part: Part::One,
+                            answer: Ok("142".to_string()),
+                            time: ms(2),
+                        },
+                        PartRun {
+                            part: Part::Two,
+                            answer: Err(Error::NoDigitFound { line: 3 }),
+                            time: ms(3),
+                        },
+                    ],
+                    wall: ms(6),
+                },
+                DayRun {
+                    number: 2,
+                    title: "Cube | Conundrum",
+                    parse: Err(Error::MissingSession),
+                    parts: vec![],
+                    wall: ms(0),
+                },
+            ],
+            wall: ms(7),
+        }
+    }
+
+    #[test]
+    fn failures_are_kept() {
+        let day = get_day(1).unwrap();
+        let summary = run_days(&[day, day], &[Part::Two], Mode::Parallel, |_| {
+            Err(Error::MissingSession)
+        });
+        assert_eq!(false, summary.is_ok());
+        assert_eq!(2, summary.days.len());
+        assert_eq!(Err(Error::MissingSession), summary.days[0].parse);
+
+        let summary = run_days(&[day], &[Part::One], Mode::Parallel, |_| {
+            Ok("1\nabc".to_string())
+        });
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 2 }),
+            summary.days[0].parts[0].answer
+        );
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            "day                 part  answer                                            parse   solve   total
+1 Trebuchet?!          1  142                                              1.00ms  2.00ms  3.00ms
+1 Trebuchet?!          2  error: line 3: no digit found                    1.00ms  3.00ms  4.00ms
+2 Cube | Conundrum     -  error: no session token is set, see AOC_SESSION       -       -  0.00ns
+total                                                                      1.00ms  5.00ms  7.00ms
+",
+            summary().to_table()
+        )
+    }
+
+    #[test]
+    fn table_keeps_errors_on_one_line() {
+        let day = get_day(2).unwrap();
+        let summary = run_days(&[day], &[Part::One], Mode::Sequential, |_| {
+            Ok("Game 1: 3 blue\nGame x: 1 red\n".to_string())
+        });
+        let table = summary.to_table();
+        assert_eq!(3, table.lines().count(), "{table}");
+        assert_eq!(
+            true,
+            table.lines().nth(1).unwrap().contains("error: line 2")
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            "| day | part | answer | parse | solve | total |
+| :-- | --: | :-- | --: | --: | --: |
+| 1 Trebuchet?! | 1 | 142 | 1.00ms | 2.00ms | 3.00ms |
+| 1 Trebuchet?! | 2 | error: line 3: no digit found | 1.00ms | 3.00ms | 4.00ms |
+| 2 Cube \\| Conundrum | - | error: no session token is set, see AOC_SESSION | - | - | 0.00ns |
+| **total** |  |  | **1.00ms** | **5.00ms** | **7.00ms** |
+",
+            summary().to_markdown()
+        )
+    }
+}
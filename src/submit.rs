@@ -0,0 +1,522 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::advent2023::Part;
+use crate::error::{Error, Result};
+use crate::input::{cache_dir, DEFAULT_BASE_URL, USER_AGENT, YEAR};
+
+/// How the server judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint on which direction the right answer is in.
+    Incorrect,
+    /// An answer was submitted too recently, the answer was not checked.
+    RateLimited,
+    /// The part was already solved, the answer was not checked.
+    AlreadySolved,
+    /// The response page could not be understood.
+    Unknown,
+}
+
+impl Verdict {
+    /// Whether the answer was checked and found to be wrong.
+    pub fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::RateLimited => "rate-limited",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unknown => "unknown",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "incorrect" => Ok(Verdict::Incorrect),
+            "rate-limited" => Ok(Verdict::RateLimited),
+            "already-solved" => Ok(Verdict::AlreadySolved),
+            "unknown" => Ok(Verdict::Unknown),
+            _ => Err(format!("unknown verdict {s}")),
+        }
+    }
+}
+
+/// Reads the verdict out of the page the server responds to a submission with.
+pub fn parse_response(html: &str) -> Verdict {
+    // The message is inside the <article> element, the rest of the page is navigation
+    let article = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    if article.contains("That's the right answer") {
+        Verdict::Correct
+    } else if article.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if article.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if article.contains("That's not the right answer") {
+        Verdict::Incorrect
+    } else if article.contains("You gave an answer too recently") {
+        Verdict::RateLimited
+    } else if article.contains("Did you already complete it") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown
+    }
+}
+
+/// A single submission recorded in the [Ledger].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Why an answer was not submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The same answer was already submitted and judged wrong.
+    KnownWrong(Verdict),
+    /// The right answer is already known and this is not it.
+    KnownCorrect(String),
+    /// An answer lower or equal to this one was already judged too high.
+    NotBelow(i64),
+    /// An answer higher or equal to this one was already judged too low.
+    NotAbove(i64),
+    /// The answer cannot be submitted, for example because it is empty.
+    Invalid,
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::KnownWrong(verdict) => write!(f, "already submitted, was {verdict}"),
+            Rejection::KnownCorrect(answer) => write!(f, "the right answer is {answer}"),
+            Rejection::NotBelow(high) => write!(f, "{high} was already too high"),
+            Rejection::NotAbove(low) => write!(f, "{low} was already too low"),
+            Rejection::Invalid => write!(f, "answers must be a single non-empty word"),
+        }
+    }
+}
+
+/// Record of every answer submitted, kept as one tab separated line per attempt so the file can be
+/// read and edited by hand.
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    /// Loads the ledger at the path, starting an empty one if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let attempts = match fs::read_to_string(&path) {
+            Ok(data) => data
+                .lines()
+                .enumerate()
+                .filter(|(_, l)| !l.trim().is_empty())
+                .map(|(index, l)| parse_attempt(index + 1, l))
+                .collect::<Result<Vec<_>>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(Error::io(&path, e)),
+        };
+
+        Ok(Ledger { path, attempts })
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Checks an answer against earlier attempts, returning why it should not be submitted if it is
+    /// already known to be wrong.
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: &str) -> Option<Rejection> {
+        if answer.is_empty() || answer.contains(char::is_whitespace) {
+            return Some(Rejection::Invalid);
+        }
+
+        let attempts = self
+            .attempts
+            .iter()
+            .filter(|a| a.year == year && a.day == day && a.part == part);
+
+        let value: Option<i64> = answer.parse().ok();
+        let mut lowest_too_high: Option<i64> = None;
+        let mut highest_too_low: Option<i64> = None;
+        for attempt in attempts {
+            if attempt.verdict == Verdict::Correct {
+                if attempt.answer == answer {
+                    return None;
+                }
+                return Some(Rejection::KnownCorrect(attempt.answer.clone()));
+            }
+            if attempt.verdict.is_wrong() && attempt.answer == answer {
+                return Some(Rejection::KnownWrong(attempt.verdict));
+            }
+            match (attempt.verdict, attempt.answer.parse::<i64>()) {
+                (Verdict::TooHigh, Ok(high)) => {
+                    lowest_too_high = Some(lowest_too_high.map_or(high, |h| h.min(high)))
+                }
+                (Verdict::TooLow, Ok(low)) => {
+                    highest_too_low = Some(highest_too_low.map_or(low, |l| l.max(low)))
+                }
+                _ => {}
+            }
+        }
+
+        match (value, lowest_too_high, highest_too_low) {
+            (Some(v), Some(high), _) if v >= high => Some(Rejection::NotBelow(high)),
+            (Some(v), _, Some(low)) if v <= low => Some(Rejection::NotAbove(low)),
+            _ => None,
+        }
+    }
+
+    /// Adds the attempt to the ledger, appending it to the file straight away.
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| Error::io(&self.path, e))?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}",
+            attempt.year, attempt.day, attempt.part, attempt.verdict, attempt.answer
+        )
+        .map_err(|e| Error::io(&self.path, e))?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+fn parse_attempt(line_number: usize, line: &str) -> Result<Attempt> {
+    let fields: Vec<_> = line.split('\t').collect();
+    let parse_error = |token: &str, expected| Error::field(line_number, line, token, expected);
+
+    let [year, day, part, verdict, answer] = fields[..] else {
+        return Err(parse_error(line, "year, day, part, verdict and answer"));
+    };
+    Ok(Attempt {
+        year: year.parse().map_err(|_| parse_error(year, "a year"))?,
+        day: day.parse().map_err(|_| parse_error(day, "a day"))?,
+        part: part.parse().map_err(|_| parse_error(part, "a part"))?,
+        verdict: verdict
+            .parse()
+            .map_err(|_| parse_error(verdict, "a verdict"))?,
+        answer: answer.to_string(),
+    })
+}
+
+/// Result of asking the [Submitter] to submit an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Submitted(Verdict),
+    Rejected(Rejection),
+}
+
+/// Submits answers to the server, recording each attempt in the [Ledger] and refusing to submit
+/// answers the ledger already knows are wrong.
+pub struct Submitter {
+    base_url: String,
+    session: String,
+    ledger: Ledger,
+}
+
+impl Submitter {
+    pub fn new(session: impl Into<String>, ledger: Ledger) -> Self {
+        Submitter {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: session.into(),
+            ledger,
+        }
+    }
+
+    /// Submitter configured from the `AOC_SESSION`, `AOC_BASE_URL` and `AOC_CACHE_DIR` environment
+    /// variables, keeping the ledger in `answers.tsv` inside the cache directory.
+    pub fn from_env() -> Result<Self> {
+        let session = std::env::var("AOC_SESSION").map_err(|_| Error::MissingSession)?;
+        let ledger = Ledger::open(cache_dir().join("answers.tsv"))?;
+
+        let mut submitter = Submitter::new(session, ledger);
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            submitter = submitter.with_base_url(base_url);
+        }
+        Ok(submitter)
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Submits the answer for the current year unless the ledger rules it out.
+    pub fn submit(&mut self, day: u8, part: Part, answer: &str) -> Result<Outcome> {
+        self.submit_for_year(YEAR, day, part, answer)
+    }
+
+    pub fn submit_for_year(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &str,
+    ) -> Result<Outcome> {
+        if let Some(rejection) = self.ledger.check(year, day, part, answer) {
+            return Ok(Outcome::Rejected(rejection));
+        }
+
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url);
+        let http_error = |message: String| Error::Http {
+            url: url.clone(),
+            message,
+        };
+        let page = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| http_error(e.to_string()))?
+            .into_string()
+            .map_err(|e| http_error(e.to_string()))?;
+
+        let verdict = parse_response(&page);
+        self.ledger.record(Attempt {
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        })?;
+        Ok(Outcome::Submitted(verdict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{parse_response, Attempt, Ledger, Outcome, Rejection, Submitter, Verdict};
+    use crate::advent2023::Part;
+    use crate::test::{serve_http, temp_dir};
+
+    fn page(message: &str) -> String {
+        format!("<html><main><article><p>{message}</p></article></main></html>")
+    }
+
+    fn attempt(part: Part, answer: &str, verdict: Verdict) -> Attempt {
+        Attempt {
+            year: 2023,
+            day: 1,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        }
+    }
+
+    #[test]
+    fn parse_response_pages() {
+        let cases = [
+            (
+                "That's the right answer! You are one gold star closer.",
+                Verdict::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "That's not the right answer. If you're stuck...",
+                Verdict::Incorrect,
+            ),
+            (
+                "You gave an answer too recently; you have 51s left to wait.",
+                Verdict::RateLimited,
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Verdict::AlreadySolved,
+            ),
+            ("Something else", Verdict::Unknown),
+        ];
+        for (message, verdict) in cases {
+            assert_eq!(verdict, parse_response(&page(message)), "{message}")
+        }
+    }
+
+    #[test]
+    fn ledger_rejects_known_wrong() {
+        let mut ledger =
+            Ledger::open(temp_dir("ledger_rejects_known_wrong").join("a.tsv")).unwrap();
+        ledger
+            .record(attempt(Part::One, "abc", Verdict::Incorrect))
+            .unwrap();
+
+        assert_eq!(
+            Some(Rejection::KnownWrong(Verdict::Incorrect)),
+            ledger.check(2023, 1, Part::One, "abc")
+        );
+        assert_eq!(None, ledger.check(2023, 1, Part::One, "abd"));
+        assert_eq!(None, ledger.check(2023, 1, Part::Two, "abc"));
+        assert_eq!(None, ledger.check(2023, 2, Part::One, "abc"));
+    }
+
+    #[test]
+    fn ledger_rejects_outside_bounds() {
+        let mut ledger =
+            Ledger::open(temp_dir("ledger_rejects_outside_bounds").join("a.tsv")).unwrap();
+        ledger
+            .record(attempt(Part::One, "100", Verdict::TooHigh))
+            .unwrap();
+        ledger
+            .record(attempt(Part::One, "90", Verdict::TooHigh))
+            .unwrap();
+        ledger
+            .record(attempt(Part::One, "10", Verdict::TooLow))
+            .unwrap();
+
+        assert_eq!(
+            Some(Rejection::NotBelow(90)),
+            ledger.check(2023, 1, Part::One, "95")
+        );
+        assert_eq!(
+            Some(Rejection::NotAbove(10)),
+            ledger.check(2023, 1, Part::One, "9")
+        );
+        assert_eq!(None, ledger.check(2023, 1, Part::One, "89"));
+        assert_eq!(None, ledger.check(2023, 1, Part::One, "11"));
+    }
+
+    #[test]
+    fn ledger_knows_correct_answer() {
+        let mut ledger =
+            Ledger::open(temp_dir("ledger_knows_correct_answer").join("a.tsv")).unwrap();
+        ledger
+            .record(attempt(Part::Two, "42", Verdict::Correct))
+            .unwrap();
+
+        assert_eq!(None, ledger.check(2023, 1, Part::Two, "42"));
+        assert_eq!(
+            Some(Rejection::KnownCorrect("42".to_string())),
+            ledger.check(2023, 1, Part::Two, "43")
+        );
+    }
+
+    #[test]
+    fn ledger_round_trips_through_file() {
+        let path = temp_dir("ledger_round_trips_through_file").join("answers.tsv");
+        let mut ledger = Ledger::open(&path).unwrap();
+        ledger
+            .record(attempt(Part::One, "100", Verdict::TooHigh))
+            .unwrap();
+        ledger
+            .record(attempt(Part::Two, "7", Verdict::RateLimited))
+            .unwrap();
+
+        assert_eq!(
+            "2023\t1\t1\ttoo-high\t100\n2023\t1\t2\trate-limited\t7\n",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(ledger.attempts(), Ledger::open(&path).unwrap().attempts());
+    }
+
+    #[test]
+    fn submit_posts_and_records() {
+        let dir = temp_dir("submit_posts_and_records");
+        let server = serve_http(vec![(
+            200,
+            page("That's not the right answer; your answer is too low."),
+        )]);
+        let ledger = Ledger::open(dir.join("answers.tsv")).unwrap();
+        let mut submitter = Submitter::new("abc", ledger).with_base_url(&server.url);
+
+        assert_eq!(
+            Ok(Outcome::Submitted(Verdict::TooLow)),
+            submitter.submit(1, Part::Two, "1234")
+        );
+        // the server only has a single response, so the ledger must answer the second one
+        assert_eq!(
+            Ok(Outcome::Rejected(Rejection::NotAbove(1234))),
+            submitter.submit(1, Part::Two, "1000")
+        );
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!(true, requests[0].starts_with("POST /2023/day/1/answer "));
+        assert_eq!(true, requests[0].contains("session=abc"));
+        assert_eq!(true, requests[0].ends_with("level=2&answer=1234"));
+        assert_eq!(
+            vec![Attempt {
+                year: 2023,
+                day: 1,
+                part: Part::Two,
+                answer: "1234".to_string(),
+                verdict: Verdict::TooLow,
+            }],
+            submitter.ledger().attempts()
+        );
+    }
+
+    #[test]
+    fn rate_limited_attempt_can_be_retried() {
+        let dir = temp_dir("rate_limited_attempt_can_be_retried");
+        let server = serve_http(vec![
+            (
+                200,
+                page("You gave an answer too recently; you have 51s left to wait."),
+            ),
+            (200, page("That's the right answer!")),
+        ]);
+        let ledger = Ledger::open(dir.join("answers.tsv")).unwrap();
+        let mut submitter = Submitter::new("abc", ledger).with_base_url(&server.url);
+
+        assert_eq!(
+            Ok(Outcome::Submitted(Verdict::RateLimited)),
+            submitter.submit(1, Part::One, "5")
+        );
+        assert_eq!(
+            Ok(Outcome::Submitted(Verdict::Correct)),
+            submitter.submit(1, Part::One, "5")
+        );
+        assert_eq!(2, server.requests().len());
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::advent2023::{Day, Part};
+use crate::error::{Error, Result};
+
+/// Step of running a day that is timed on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [
+        Phase::Parse,
+        Phase::Solve(Part::One),
+        Phase::Solve(Part::Two),
+    ];
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+impl FromStr for Phase {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s == "parse" {
+            return Ok(Phase::Parse);
+        }
+        match s.strip_prefix("part ").map(str::parse) {
+            Some(Ok(part)) => Ok(Phase::Solve(part)),
+            _ => Err(format!(
+                "unknown phase {s}, expected parse, part 1 or part 2"
+            )),
+        }
+    }
+}
+
+/// Number of timed samples the median is taken from.
+const SAMPLES: usize = 21;
+/// Shortest time a sample should take, so that the timer's resolution does not matter.
+const MIN_SAMPLE_TIME: Duration = Duration::from_millis(1);
+
+/// Median time of a single run of `f`. Runs are timed in batches large enough to take at least a
+/// millisecond, after a first batch used to warm up and to size the batches.
+pub fn median_time<T>(mut f: impl FnMut() -> T) -> Duration {
+    let mut batch: u32 = 1;
+    loop {
+        let start = Instant::now();
+        for _ in 0..batch {
+            black_box(f());
+        }
+        if start.elapsed() >= MIN_SAMPLE_TIME || batch >= 1 << 20 {
+            break;
+        }
+        batch *= 2;
+    }
+
+    let mut samples: Vec<_> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..batch {
+                black_box(f());
+            }
+            start.elapsed() / batch
+        })
+        .collect();
+    samples.sort_unstable();
+    samples[SAMPLES / 2]
+}
+
+/// Median time of parsing the input and of solving each part from the parsed input. Fails if the
+/// day can not parse the input or solve either part, as a broken day has nothing worth timing.
+pub fn time_day(day: &Day, input: &str) -> Result<Vec<(Phase, Duration)>> {
+    let parsed = day.parse(input)?;
+    for part in Part::BOTH {
+        parsed.solve(part)?;
+    }
+
+    Ok(Phase::ALL
+        .into_iter()
+        .map(|phase| {
+            let time = match phase {
+                Phase::Parse => median_time(|| day.parse(black_box(input))),
+                Phase::Solve(part) => median_time(|| parsed.solve(part)),
+            };
+            (phase, time)
+        })
+        .collect())
+}
+
+/// A phase that got slower than it was in the baseline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub day: u8,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let change = self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0;
+        write!(
+            f,
+            "day {} {}: {:?} -> {:?} (+{:.0}%)",
+            self.day,
+            self.phase,
+            self.baseline,
+            self.current,
+            change * 100.0
+        )
+    }
+}
+
+/// Times of every phase of every day, kept in a file between commits so that a later run can be
+/// compared against it. The file has a line per phase with the day, the phase and the time in
+/// nanoseconds separated by tabs. Times depend on the machine, so a baseline is only worth comparing
+/// against on the machine it was recorded on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    times: BTreeMap<(u8, Phase), Duration>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Baseline::default()
+    }
+
+    pub fn insert(&mut self, day: u8, phase: Phase, time: Duration) {
+        self.times.insert((day, phase), time);
+    }
+
+    pub fn get(&self, day: u8, phase: Phase) -> Option<Duration> {
+        self.times.get(&(day, phase)).copied()
+    }
+
+    /// Reads a baseline from the file, which is empty if the file does not exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Baseline::new()),
+            Err(e) => return Err(Error::io(&path, e)),
+        };
+        data.parse()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        fs::write(path, self.to_string()).map_err(|e| Error::io(path, e))
+    }
+
+    /// The phases that take more than `tolerance` longer in `current` than in this baseline, where a
+    /// tolerance of 0.25 allows 25% more time. Phases missing from either side are not compared.
+    pub fn regressions(&self, current: &Baseline, tolerance: f64) -> Vec<Regression> {
+        self.times
+            .iter()
+            .filter_map(|(&(day, phase), &baseline)| {
+                let current = current.get(day, phase)?;
+                let slower = current.as_secs_f64() > baseline.as_secs_f64() * (1.0 + tolerance);
+                slower.then_some(Regression {
+                    day,
+                    phase,
+                    baseline,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    /// The phases timed in `current` that this baseline has no time for, such as those of a day
+    /// added since the baseline was saved.
+    pub fn unrecorded(&self, current: &Baseline) -> Vec<(u8, Phase)> {
+        current
+            .times
+            .keys()
+            .filter(|key| !self.times.contains_key(key))
+            .copied()
+            .collect()
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for ((day, phase), time) in &self.times {
+            writeln!(f, "{day}\t{phase}\t{}", time.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut baseline = Baseline::new();
+        for (index, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parse_error =
+                |token: &str, expected| Error::field(index + 1, line, token, expected);
+
+            let [day, phase, nanos] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return Err(parse_error(line, "day, phase and nanoseconds"));
+            };
+            baseline.insert(
+                day.parse().map_err(|_| parse_error(day, "a day"))?,
+                phase.parse().map_err(|_| parse_error(phase, "a phase"))?,
+                Duration::from_nanos(
+                    nanos
+                        .parse()
+                        .map_err(|_| parse_error(nanos, "a number of nanoseconds"))?,
+                ),
+            );
+        }
+        Ok(baseline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{time_day, Baseline, Phase, Regression};
+    use crate::advent2023::{get_day, Part};
+    use crate::error::Error;
+    use crate::test::temp_dir;
+
+    #[test]
+    fn phase_round_trip() {
+        for phase in Phase::ALL {
+            assert_eq!(Ok(phase), phase.to_string().parse());
+        }
+        assert_eq!(true, "part 3".parse::<Phase>().is_err());
+        assert_eq!(true, "solve".parse::<Phase>().is_err());
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let dir = temp_dir("baseline_round_trip");
+        let path = dir.join("baseline.tsv");
+        assert_eq!(Ok(Baseline::new()), Baseline::load(&path));
+
+        let mut baseline = Baseline::new();
+        baseline.insert(2, Phase::Solve(Part::Two), Duration::from_nanos(1500));
+        baseline.insert(1, Phase::Parse, Duration::from_micros(3));
+        baseline.save(&path).unwrap();
+
+        assert_eq!(
+            "1\tparse\t3000\n2\tpart 2\t1500\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(Ok(baseline), Baseline::load(&path));
+    }
+
+    #[test]
+    fn baseline_bad_line() {
+        assert_eq!(
+            Err(Error::Parse {
+                line: 2,
+                column: 3,
+                token: "part one".to_string(),
+                expected: "a phase",
+                excerpt: "1\tpart one\t10".to_string(),
+            }),
+            "1\tparse\t10\n1\tpart one\t10".parse::<Baseline>()
+        )
+    }
+
+    #[test]
+    fn regressions_beyond_tolerance() {
+        let mut before = Baseline::new();
+        before.insert(1, Phase::Parse, Duration::from_micros(100));
+        before.insert(1, Phase::Solve(Part::One), Duration::from_micros(100));
+        before.insert(2, Phase::Parse, Duration::from_micros(100));
+        let mut after = Baseline::new();
+        after.insert(1, Phase::Parse, Duration::from_micros(120));
+        after.insert(1, Phase::Solve(Part::One), Duration::from_micros(150));
+
+        let regressions = before.regressions(&after, 0.25);
+        assert_eq!(
+            vec![Regression {
+                day: 1,
+                phase: Phase::Solve(Part::One),
+                baseline: Duration::from_micros(100),
+                current: Duration::from_micros(150),
+            }],
+            regressions
+        );
+        assert_eq!(
+            "day 1 part 1: 100µs -> 150µs (+50%)",
+            regressions[0].to_string()
+        );
+    }
+
+    #[test]
+    fn unrecorded_phases() {
+        let mut before = Baseline::new();
+        before.insert(1, Phase::Parse, Duration::from_micros(100));
+        let mut after = Baseline::new();
+        after.insert(1, Phase::Parse, Duration::from_micros(100));
+        after.insert(3, Phase::Parse, Duration::from_micros(100));
+        after.insert(3, Phase::Solve(Part::Two), Duration::from_micros(100));
+        assert_eq!(
+            vec![(3, Phase::Parse), (3, Phase::Solve(Part::Two))],
+            before.unrecorded(&after)
+        );
+        assert_eq!(Vec::<(u8, Phase)>::new(), after.unrecorded(&before));
+    }
+
+    #[test]
+    fn times_every_phase() {
+        let day = get_day(1).unwrap();
+        let times = time_day(day, "1abc2\ntreb7uchet").unwrap();
+        let phases: Vec<_> = times.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(Phase::ALL.to_vec(), phases);
+        assert_eq!(
+            Err(Error::NoDigitFound { line: 1 }),
+            time_day(day, "abc").map(|_| ())
+        );
+    }
+}